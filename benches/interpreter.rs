@@ -0,0 +1,122 @@
+// scripts the interpreter spends its time on, run with cargo bench
+use rlox::{
+    diagnostic::SourceFile,
+    interpreter::{io::Io, Interpreter},
+    parser::Parser,
+    scanner::{dialect::Dialect, Scanner},
+};
+use std::{
+    hint::black_box,
+    io,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+const SCRIPTS: [(&str, &str); 6] = [
+    (
+        "concat",
+        r#"var s = ""; var i = 0;
+        while (i < 2000) { s = s + "x"; i = i + 1; }"#,
+    ),
+    (
+        "pass strings",
+        r#"var text = repeat("lorem ipsum dolor sit amet ", 2000);
+        var copy = text; var i = 0;
+        while (i < 2000) { copy = text; var other = copy; i = i + 1; }"#,
+    ),
+    (
+        "long names",
+        r#"var a_rather_long_variable_name_to_hash = 0;
+        while (a_rather_long_variable_name_to_hash < 5000) {
+            a_rather_long_variable_name_to_hash = a_rather_long_variable_name_to_hash + 1;
+        }"#,
+    ),
+    (
+        "split and join",
+        r#"var words = split(repeat("word ", 500), " "); var i = 0;
+        while (i < 200) { var line = join(words, ","); print len(line); i = i + 1; }"#,
+    ),
+    (
+        "fib",
+        r#"var n = 0;
+        while (n < 300) {
+            var a = 0; var b = 1; var i = 0;
+            while (i < 60) { var next = a + b; a = b; b = next; i = i + 1; }
+            n = n + 1;
+        }"#,
+    ),
+    (
+        "nested loops",
+        r#"var total = 0; var i = 0;
+        while (i < 100) {
+            var j = 0;
+            while (j < 100) {
+                if (j - (j / 2) * 2 == 0) { total = total + i * j; } else { total = total - 1; }
+                j = j + 1;
+            }
+            i = i + 1;
+        }"#,
+    ),
+];
+
+// prints go nowhere so the terminal isn't measured
+struct Discard;
+
+impl Io for Discard {
+    fn print(&mut self, _: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_line(&mut self, _: &str) -> io::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn read_file(&mut self, _: &str) -> io::Result<String> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn write_file(&mut self, _: &str, _: &str) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn append_file(&mut self, _: &str, _: &str) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn file_exists(&mut self, _: &str) -> io::Result<bool> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn list_dir(&mut self, _: &str) -> io::Result<Vec<String>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+fn run(script: &str) {
+    let source = SourceFile::new("<bench>", script.to_string());
+    let tokens = match Scanner::new(source, Rc::new(Dialect::default())).scan_tokens() {
+        Ok(tokens) => tokens.to_vec(),
+        Err(_) => panic!("the script doesn't scan"),
+    };
+    let ast = match Parser::new(tokens).parse() {
+        Ok(ast) => ast,
+        Err(_) => panic!("the script doesn't parse"),
+    };
+    let mut interpreter = Interpreter::with_io(Box::new(Discard));
+    if let Err(err) = interpreter.interpret(&ast) {
+        panic!("the script failed: {}", err);
+    }
+}
+
+fn main() {
+    for (name, script) in SCRIPTS {
+        run(script);
+        let (mut runs, start) = (0, Instant::now());
+        while start.elapsed() < Duration::from_secs(2) {
+            run(black_box(script));
+            runs += 1;
+        }
+        let per_run = start.elapsed() / runs;
+        println!("{:<16} {:>12?} per run ({} runs)", name, per_run, runs);
+    }
+}
@@ -0,0 +1,448 @@
+use rlox::interpreter::{
+    limits::Limits,
+    sandbox::{Capability, Sandbox},
+};
+use std::{path::PathBuf, time::Duration};
+
+pub const USAGE: &str = "\
+Usage: rlox [options] [script] [args...]
+       rlox [options] run <script> [args...]
+       rlox [options] repl
+       rlox translate [--from <dialect>] [--to <dialect>] <script>
+       rlox [options] fmt [--check] <script>
+       rlox [options] -e <code> [args...]
+       rlox presets list
+       rlox presets show <name>
+
+Commands:
+  run <script>        Run a script file, use '-' to read the script from stdin
+  repl                Start the interactive prompt (default when no script is given)
+  translate <script>  Rewrite the script from one keyword dialect to another, a dialect is
+                      a bundled preset, the path of a .tokenfile or lox (the default)
+  fmt <script>        Print the script formatted, with --check only tell whether it already is
+  presets list        List the presets bundled with rlox
+  presets show <name> Print the .tokenfile rules of a bundled preset
+
+Options:
+  -e, --eval <code>   Run <code> instead of a script file
+  --tokenfile <path>  Use the keyword remaps defined in <path>
+  --preset <name>     Use the keyword remaps of a bundled preset
+  --sandbox           Don't let the script use files, environment variables, time or input
+  --allow <groups>    Let a sandboxed script use some of fs, env, time, process and network,
+                      separated by ',', implies --sandbox
+  --max-steps <n>     Stop the script after it executed <n> statements and loop iterations
+  --timeout <seconds> Stop the script after it ran for <seconds>
+  --max-memory <n>    Stop the script once its variables hold more than <n> bytes
+  --max-nesting <n>   Refuse scripts with statements or expressions nested deeper than <n>
+  -O, --optimize      Fold constant expressions and drop branches that can't run
+  --gc-stress         Collect garbage on every allocation
+  --gc-stats          Print garbage collector statistics when the script ends
+  -h, --help          Print this help and exit
+  -V, --version       Print version information and exit
+
+Arguments following the script are available to the program through the `args` global.
+
+Without --tokenfile or --preset the first .tokenfile found is used, looking next to the
+script, then in the current directory, then in the rlox folder of the user config directory.";
+
+// where the source code of the program comes from
+pub enum Script {
+    File(String),
+    Stdin,
+    Inline(String),
+}
+
+pub enum Command {
+    Run {
+        script: Script,
+        args: Vec<String>,
+    },
+    Repl,
+    Translate {
+        from: String,
+        to: String,
+        script: Script,
+    },
+    Fmt {
+        script: Script,
+        check: bool,
+    },
+    PresetList,
+    PresetShow(String),
+    Help,
+    Version,
+}
+
+// selects which keyword remaps the scanner uses
+pub enum KeywordSource {
+    Tokenfile(PathBuf),
+    Preset(String),
+}
+
+pub struct Cli {
+    pub command: Command,
+    pub keywords: Option<KeywordSource>,
+    pub sandbox: Option<Sandbox>,
+    pub limits: Limits,
+    pub optimize: bool,
+    pub gc_stress: bool,
+    pub gc_stats: bool,
+}
+
+// options given before the command
+#[derive(Default)]
+struct Options {
+    keywords: Option<KeywordSource>,
+    sandbox: Option<Sandbox>,
+    limits: Limits,
+    optimize: bool,
+    gc_stress: bool,
+    gc_stats: bool,
+}
+
+impl Cli {
+    // parses command line arguments (without the executable name)
+    pub fn parse(arguments: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+        let mut arguments = arguments.into_iter();
+        let mut options = Options::default();
+        let mut subcommand: Option<String> = None;
+
+        while let Some(argument) = arguments.next() {
+            if let Some((flag, value)) = argument.split_once('=') {
+                if flag.starts_with("--") {
+                    options.set(flag, value.to_string())?;
+                    continue;
+                }
+            }
+            match argument.as_str() {
+                "-h" | "--help" => return Ok(Cli::new(Command::Help, options)),
+                "-V" | "--version" => return Ok(Cli::new(Command::Version, options)),
+                "--tokenfile" | "--preset" | "--allow" | "--max-steps" | "--timeout"
+                | "--max-memory" | "--max-nesting" => {
+                    let value = next_value(&mut arguments, &argument)?;
+                    options.set(&argument, value)?;
+                }
+                "-O" | "--optimize" => options.optimize = true,
+                "--gc-stress" => options.gc_stress = true,
+                "--gc-stats" => options.gc_stats = true,
+                "--sandbox" => {
+                    options.sandbox.get_or_insert_with(Sandbox::locked);
+                }
+                "-e" | "--eval" => {
+                    let code = next_value(&mut arguments, &argument)?;
+                    let command = Command::Run {
+                        script: Script::Inline(code),
+                        args: script_arguments(arguments),
+                    };
+                    return Ok(Cli::new(command, options));
+                }
+                "run" if subcommand.is_none() => subcommand = Some(argument),
+                "translate" if subcommand.is_none() => {
+                    return Ok(Cli::new(translate(arguments)?, options));
+                }
+                "fmt" if subcommand.is_none() => {
+                    return Ok(Cli::new(fmt(arguments)?, options));
+                }
+                "presets" if subcommand.is_none() => {
+                    let command = match (arguments.next().as_deref(), arguments.next()) {
+                        (Some("list") | None, None) => Command::PresetList,
+                        (Some("show"), Some(name)) => Command::PresetShow(name),
+                        _ => {
+                            return Err(String::from(
+                                "Expect 'presets list' or 'presets show <name>'.",
+                            ))
+                        }
+                    };
+                    match arguments.next() {
+                        None => return Ok(Cli::new(command, options)),
+                        Some(extra) => {
+                            return Err(format!("Unexpected argument '{}' after presets.", extra))
+                        }
+                    }
+                }
+                "repl" if subcommand.is_none() => {
+                    return match arguments.next() {
+                        None => Ok(Cli::new(Command::Repl, options)),
+                        Some(extra) => Err(format!("Unexpected argument '{}' after repl.", extra)),
+                    };
+                }
+                "--" => {
+                    let script = next_value(&mut arguments, "--")?;
+                    return Cli::script(script, arguments, options);
+                }
+                "-" => return Cli::script(argument, arguments, options),
+                flag if flag.starts_with('-') => {
+                    return Err(format!("Unknown option '{}'.", flag));
+                }
+                _ => return Cli::script(argument, arguments, options),
+            }
+        }
+
+        match subcommand {
+            Some(subcommand) => Err(format!("Expect a script after {}.", subcommand)),
+            None => Ok(Cli::new(Command::Repl, options)),
+        }
+    }
+
+    fn new(command: Command, options: Options) -> Cli {
+        Cli {
+            command,
+            keywords: options.keywords,
+            sandbox: options.sandbox,
+            limits: options.limits,
+            optimize: options.optimize,
+            gc_stress: options.gc_stress,
+            gc_stats: options.gc_stats,
+        }
+    }
+
+    // builds the command taking a script, rlox file.lox is short for rlox run file.lox
+    fn script(
+        script: String,
+        arguments: impl Iterator<Item = String>,
+        options: Options,
+    ) -> Result<Cli, String> {
+        let script = if script == "-" {
+            Script::Stdin
+        } else {
+            Script::File(script)
+        };
+        let command = Command::Run {
+            script,
+            args: script_arguments(arguments),
+        };
+        Ok(Cli::new(command, options))
+    }
+}
+
+fn translate(mut arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut from = String::from("lox");
+    let mut to = String::from("lox");
+    let mut script = None;
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--from" => from = next_value(&mut arguments, &argument)?,
+            "--to" => to = next_value(&mut arguments, &argument)?,
+            "-" if script.is_none() => script = Some(Script::Stdin),
+            flag if flag.starts_with('-') => {
+                return Err(format!("Unknown option '{}'.", flag));
+            }
+            _ if script.is_none() => script = Some(Script::File(argument)),
+            _ => {
+                return Err(format!(
+                    "Unexpected argument '{}' after translate.",
+                    argument
+                ))
+            }
+        }
+    }
+    match script {
+        Some(script) => Ok(Command::Translate { from, to, script }),
+        None => Err(String::from("Expect a script after translate.")),
+    }
+}
+
+fn fmt(arguments: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut check = false;
+    let mut script = None;
+    for argument in arguments {
+        match argument.as_str() {
+            "--check" => check = true,
+            "-" if script.is_none() => script = Some(Script::Stdin),
+            flag if flag.starts_with('-') => {
+                return Err(format!("Unknown option '{}'.", flag));
+            }
+            _ if script.is_none() => script = Some(Script::File(argument)),
+            _ => return Err(format!("Unexpected argument '{}' after fmt.", argument)),
+        }
+    }
+    match script {
+        Some(script) => Ok(Command::Fmt { script, check }),
+        None => Err(String::from("Expect a script after fmt.")),
+    }
+}
+
+fn next_value(arguments: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    arguments
+        .next()
+        .ok_or_else(|| format!("Expect a value after '{}'.", flag))
+}
+
+impl Options {
+    // applies an option taking a value
+    fn set(&mut self, flag: &str, value: String) -> Result<(), String> {
+        match flag {
+            "--tokenfile" => self.keywords = Some(KeywordSource::Tokenfile(PathBuf::from(value))),
+            "--preset" => self.keywords = Some(KeywordSource::Preset(value)),
+            "--allow" => {
+                let sandbox = self.sandbox.get_or_insert_with(Sandbox::locked);
+                for name in value.split(',') {
+                    match Capability::find(name.trim()) {
+                        Some(capability) => sandbox.allow(capability),
+                        None => {
+                            return Err(format!(
+                            "Unknown capability '{}', expect fs, env, time, process or network.",
+                            name
+                        ))
+                        }
+                    }
+                }
+            }
+            "--max-steps" => match value.parse() {
+                Ok(steps) => self.limits.steps = Some(steps),
+                Err(_) => return Err(format!("Expect a number of steps, got '{}'.", value)),
+            },
+            "--max-memory" => match value.parse() {
+                Ok(bytes) => self.limits.memory = Some(bytes),
+                Err(_) => return Err(format!("Expect a number of bytes, got '{}'.", value)),
+            },
+            "--max-nesting" => match value.parse() {
+                Ok(nesting) => self.limits.nesting = nesting,
+                Err(_) => return Err(format!("Expect a nesting depth, got '{}'.", value)),
+            },
+            "--timeout" => match value.parse().map(Duration::try_from_secs_f64) {
+                Ok(Ok(timeout)) => self.limits.timeout = Some(timeout),
+                _ => return Err(format!("Expect a number of seconds, got '{}'.", value)),
+            },
+            _ => return Err(format!("Unknown option '{}'.", flag)),
+        }
+        Ok(())
+    }
+}
+
+// everything after the script belongs to the script, a leading "--" is dropped
+fn script_arguments(arguments: impl Iterator<Item = String>) -> Vec<String> {
+    let mut arguments = arguments.peekable();
+    if arguments.peek().map(String::as_str) == Some("--") {
+        arguments.next();
+    }
+    arguments.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlox::{
+        diagnostic::SourceFile,
+        error::RloxError,
+        interpreter::Interpreter,
+        parser::Parser,
+        scanner::{dialect::Dialect, Scanner},
+    };
+    use std::rc::Rc;
+
+    fn parse(arguments: &[&str]) -> Result<Cli, String> {
+        Cli::parse(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    // the script file and arguments of a run command
+    fn run(cli: Cli) -> (String, Vec<String>) {
+        match cli.command {
+            Command::Run {
+                script: Script::File(path),
+                args,
+            } => (path, args),
+            _ => unreachable!("only run commands with a script file are tested"),
+        }
+    }
+
+    // how running source as a script fails
+    fn failure(source: &str) -> RloxError {
+        let source = SourceFile::new("test.lox", source.to_string());
+        let mut scanner = Scanner::new(source, Rc::new(Dialect::default()));
+        let tokens = match scanner.scan_tokens() {
+            Ok(tokens) => tokens.to_vec(),
+            Err(err) => return err,
+        };
+        let ast = match Parser::new(tokens).parse() {
+            Ok(ast) => ast,
+            Err(errors) => return RloxError::from(errors),
+        };
+        match Interpreter::new().interpret(&ast) {
+            Ok(_) => unreachable!("only failing scripts are tested"),
+            Err(err) => RloxError::from(err),
+        }
+    }
+
+    #[test]
+    fn options_can_take_their_value_after_an_equals_sign() {
+        let cli = parse(&["--max-steps=10", "--preset=pirate", "main.lox", "a"]).unwrap();
+        assert!(cli.limits.steps == Some(10));
+        assert!(matches!(&cli.keywords, Some(KeywordSource::Preset(name)) if name == "pirate"));
+        let (script, args) = run(cli);
+        assert!(script == "main.lox" && args == ["a"]);
+        assert!(parse(&["--max-steps=ten", "main.lox"])
+            .err()
+            .unwrap()
+            .contains("Expect a number of steps"));
+    }
+
+    #[test]
+    fn double_dash_ends_the_options() {
+        let (script, args) = run(parse(&["--", "-odd.lox", "--gc-stats"]).unwrap());
+        assert!(script == "-odd.lox" && args == ["--gc-stats"]);
+        let (script, args) = run(parse(&["main.lox", "--", "-x"]).unwrap());
+        assert!(script == "main.lox" && args == ["-x"]);
+        assert!(parse(&["--"])
+            .err()
+            .unwrap()
+            .contains("Expect a value after '--'"));
+    }
+
+    #[test]
+    fn eval_passes_what_follows_the_code_to_the_script() {
+        let cli = parse(&["-O", "-e", "print args;", "a", "b"]).unwrap();
+        assert!(cli.optimize);
+        assert!(matches!(
+            cli.command,
+            Command::Run { script: Script::Inline(code), args }
+                if code == "print args;" && args == ["a", "b"]
+        ));
+        assert!(parse(&["-e"])
+            .err()
+            .unwrap()
+            .contains("Expect a value after '-e'"));
+    }
+
+    #[test]
+    fn unknown_options_are_errors() {
+        for arguments in [
+            &["--nope"][..],
+            &["--nope=1"],
+            &["fmt", "--nope", "main.lox"],
+        ] {
+            let error = parse(arguments).err().unwrap();
+            assert!(error.starts_with("Unknown option '--nope"), "{}", error);
+        }
+        assert!(parse(&["--allow", "disk"])
+            .err()
+            .unwrap()
+            .starts_with("Unknown capability 'disk'"));
+    }
+
+    #[test]
+    fn presets_show_takes_exactly_one_name() {
+        assert!(matches!(
+            parse(&["presets"]).unwrap().command,
+            Command::PresetList
+        ));
+        assert!(matches!(
+            parse(&["presets", "show", "pirate"]).unwrap().command,
+            Command::PresetShow(name) if name == "pirate"
+        ));
+        assert!(parse(&["presets", "show"]).is_err());
+        assert!(parse(&["presets", "show", "pirate", "doge"]).is_err());
+        assert!(parse(&["presets", "list", "all"]).is_err());
+    }
+
+    #[test]
+    fn errors_exit_with_sysexits_codes() {
+        assert_eq!(RloxError::Usage(String::new()).exit_code(), 64);
+        assert_eq!(failure("print \"open;").exit_code(), 65);
+        assert_eq!(failure("print 1").exit_code(), 65);
+        assert_eq!(RloxError::NoInput(String::new()).exit_code(), 66);
+        assert_eq!(failure("print x;").exit_code(), 70);
+        let config = Dialect::from_rules("loop : forever").err().unwrap();
+        assert_eq!(config.exit_code(), 78);
+    }
+}
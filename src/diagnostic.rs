@@ -0,0 +1,116 @@
+use crate::{
+    messages::{Code, Message},
+    token::Token,
+};
+use std::{fmt, rc::Rc};
+
+// a piece of source code along with the name it is reported under
+#[derive(PartialEq)]
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+}
+
+impl SourceFile {
+    pub fn new(name: &str, text: String) -> Rc<Self> {
+        Rc::new(SourceFile {
+            name: name.to_string(),
+            text,
+        })
+    }
+
+    // returns the full line containing the byte at offset without its line ending
+    fn line_at(&self, offset: usize) -> &str {
+        let offset = offset.min(self.text.len());
+        let start = self.text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |i| offset + i);
+        self.text[start..end].trim_end_matches('\r')
+    }
+}
+
+// an error pointing at a location in the source, rendered like rustc does:
+//
+// error[E0204]: Expect ';' after value.
+//  --> main.lox:1:8
+//   |
+// 1 | print a
+//   |        ^
+//   = help: add ';' at the end of the statement
+pub struct Diagnostic {
+    pub code: Code,
+    pub message: String,
+    pub source: Rc<SourceFile>,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub len: usize,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(
+        message: &Message,
+        source: Rc<SourceFile>,
+        line: usize,
+        column: usize,
+        offset: usize,
+        len: usize,
+    ) -> Self {
+        Diagnostic {
+            code: message.code,
+            message: message.text.clone(),
+            source,
+            line,
+            column,
+            offset,
+            len,
+            help: message.help.clone(),
+        }
+    }
+
+    // diagnostic underlining the whole lexeme of token
+    pub fn at_token(token: &Token, message: &Message) -> Self {
+        Diagnostic::new(
+            message,
+            token.source.clone(),
+            token.line,
+            token.column,
+            token.offset,
+            token.lexeme.chars().count(),
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let text = self.source.line_at(self.offset);
+        // keep tabs so the carets line up with the source line above them
+        let padding: String = text
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        writeln!(f, "error[{}]: {}", self.code.id(), self.message)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter, self.source.name, self.line, self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, text)?;
+        // lexemes spanning several lines are only underlined up to the end of the first one
+        let len = self
+            .len
+            .min(text.chars().count().saturating_sub(padding.len()));
+        write!(f, "{} | {}{}", gutter, padding, "^".repeat(len.max(1)))?;
+        if let Some(help) = &self.help {
+            write!(f, "\n{} = help: {}", gutter, help)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    diagnostic::Diagnostic, interpreter::RuntimeError, messages::Messages, parser::ParseError,
+    scanner::error::ScanError,
+};
+use std::fmt;
+
+// exit codes follow the conventions of sysexits.h
+pub const EX_UNFORMATTED: i32 = 1; // fmt --check found a script that isn't formatted
+pub const EX_USAGE: i32 = 64; // the command was used incorrectly
+pub const EX_DATAERR: i32 = 65; // the script could not be scanned, parsed or translated
+pub const EX_NOINPUT: i32 = 66; // the script could not be read
+pub const EX_SOFTWARE: i32 = 70; // the script failed while running
+pub const EX_CONFIG: i32 = 78; // the .tokenfile is invalid
+
+// every failure that can stop rlox ends up as one of these in main
+pub enum RloxError {
+    Usage(String),
+    Scan(Vec<ScanError>),
+    Parse(Vec<ParseError>),
+    Translate(Diagnostic),
+    NoInput(String),
+    Runtime(RuntimeError),
+    Config(String),
+    Unformatted(String),
+}
+
+impl RloxError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RloxError::Usage(_) => EX_USAGE,
+            RloxError::Scan(_) | RloxError::Parse(_) | RloxError::Translate(_) => EX_DATAERR,
+            RloxError::NoInput(_) => EX_NOINPUT,
+            RloxError::Runtime(_) => EX_SOFTWARE,
+            RloxError::Config(_) => EX_CONFIG,
+            RloxError::Unformatted(_) => EX_UNFORMATTED,
+        }
+    }
+
+    // rewrites diagnostics of the script with the messages of its dialect
+    pub fn localize(mut self, messages: &Messages) -> Self {
+        match &mut self {
+            RloxError::Scan(errors) => errors.iter_mut().for_each(|e| e.localize(messages)),
+            RloxError::Parse(errors) => errors.iter_mut().for_each(|e| e.localize(messages)),
+            RloxError::Runtime(error) => error.localize(messages),
+            _ => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for RloxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RloxError::Usage(msg) => write!(f, "{}", msg),
+            RloxError::Scan(errors) => {
+                let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", errors.join("\n\n"))
+            }
+            RloxError::Parse(errors) => {
+                let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", errors.join("\n\n"))
+            }
+            RloxError::Translate(diagnostic) => write!(f, "{}", diagnostic),
+            RloxError::NoInput(msg) => write!(f, "{}", msg),
+            RloxError::Runtime(error) => write!(f, "{}", error),
+            RloxError::Config(msg) => write!(f, "Invalid .tokenfile : {}", msg),
+            RloxError::Unformatted(name) => {
+                write!(f, "{} is not formatted, run rlox fmt on it", name)
+            }
+        }
+    }
+}
+
+impl From<RuntimeError> for RloxError {
+    fn from(error: RuntimeError) -> Self {
+        RloxError::Runtime(error)
+    }
+}
+
+impl From<Vec<ParseError>> for RloxError {
+    fn from(errors: Vec<ParseError>) -> Self {
+        RloxError::Parse(errors)
+    }
+}
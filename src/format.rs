@@ -0,0 +1,435 @@
+use crate::{
+    diagnostic::SourceFile,
+    error::RloxError,
+    object::Object,
+    parser::{
+        ast::{Ast, ExprId, StmtId},
+        expr::Expr,
+        stmt::Stmt,
+        Parser,
+    },
+    scanner::{dialect::Dialect, token::Tokentype, Scanner},
+    translate::needs_space,
+};
+use std::rc::Rc;
+
+const INDENT: &str = "    ";
+
+// renders the program back to source in one canonical layout: a statement per line, blocks
+// indented by four spaces with the brace on the line that opens them and single spaces around
+// operators. keywords are spelled the way the dialect spells them, number and string literals are
+// copied as written and comments stay next to the statements they were written by. formatting
+// formatted source gives the same source back
+pub fn format(source: Rc<SourceFile>, dialect: &Rc<Dialect>) -> Result<String, RloxError> {
+    let tokens = Scanner::new(source.clone(), dialect.clone())
+        .scan_tokens()?
+        .to_vec();
+
+    // comments aren't tokens, they are whatever isn't whitespace between two tokens
+    let text = &source.text;
+    let mut comments = Vec::new();
+    let mut last = 0;
+    for token in &tokens {
+        find_comments(text, last, token.offset, dialect, &mut comments);
+        last = token.offset + token.lexeme.len();
+    }
+    find_comments(text, last, text.len(), dialect, &mut comments);
+
+    let ast = Parser::new(tokens).parse()?;
+    let mut formatter = Formatter {
+        ast: &ast,
+        text,
+        dialect,
+        comments,
+        next: 0,
+        output: String::new(),
+        indent: 0,
+        last: 0,
+        fresh: true,
+    };
+    for statement in &ast.statements {
+        formatter.statement(*statement);
+    }
+    formatter.comments(text.len() + 1);
+    Ok(formatter.output)
+}
+
+// byte offsets of a comment, end is exclusive
+struct Comment {
+    start: usize,
+    end: usize,
+}
+
+// collects the comments of text between start and end, which holds no tokens
+fn find_comments(
+    text: &str,
+    start: usize,
+    end: usize,
+    dialect: &Dialect,
+    comments: &mut Vec<Comment>,
+) {
+    let gap = &text[start..end];
+    let mut index = 0;
+    while index < gap.len() {
+        let rest = &gap[index..];
+        let length = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            block_comment_length(rest, dialect)
+        } else {
+            index += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        comments.push(Comment {
+            start: start + index,
+            end: start + index + rest[..length].trim_end().len(),
+        });
+        index += length;
+    }
+}
+
+// rest starts with "/*", the scanner already made sure the comment is closed
+fn block_comment_length(rest: &str, dialect: &Dialect) -> usize {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < rest.len() {
+        if rest[index..].starts_with("/*") && (depth == 0 || dialect.features.nested_comments) {
+            depth += 1;
+            index += 2;
+        } else if rest[index..].starts_with("*/") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return index;
+            }
+        } else {
+            index += rest[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    rest.len()
+}
+
+struct Formatter<'a> {
+    ast: &'a Ast,
+    text: &'a str,
+    dialect: &'a Dialect,
+    comments: Vec<Comment>,
+    // the first comment not written yet
+    next: usize,
+    output: String,
+    indent: usize,
+    // where the last thing written ended in the source
+    last: usize,
+    // nothing was written into the current block yet, it doesn't start with a blank line
+    fresh: bool,
+}
+
+impl Formatter<'_> {
+    // writes a statement on lines of its own
+    fn statement(&mut self, id: StmtId) {
+        let span = self.ast.stmt_span(id);
+        self.comments(self.head_end(id));
+        self.line(span.start);
+        self.statement_body(id);
+        self.trailing_comment(span.end);
+        self.output.push('\n');
+    }
+
+    // comments in the part of a statement before its body are moved above it
+    fn head_end(&self, id: StmtId) -> usize {
+        match self.ast.stmt(id) {
+            Stmt::If { then_branch, .. } => self.ast.stmt_span(*then_branch).start,
+            Stmt::While { body, .. } => self.ast.stmt_span(*body).start,
+            Stmt::Block { .. } => self.ast.stmt_span(id).start,
+            _ => self.ast.stmt_span(id).end,
+        }
+    }
+
+    fn statement_body(&mut self, id: StmtId) {
+        match self.ast.stmt(id) {
+            Stmt::Expression { expression } => {
+                let expression = self.expression(*expression);
+                self.push(&expression);
+                self.token(Tokentype::Semicolon);
+            }
+            Stmt::Print { expression, .. } => {
+                let expression = self.expression(*expression);
+                self.token(Tokentype::Print);
+                self.push(" ");
+                self.push(&expression);
+                self.token(Tokentype::Semicolon);
+            }
+            Stmt::Var {
+                name, initalizer, ..
+            } => {
+                self.token(Tokentype::Var);
+                self.push(" ");
+                self.push(&name.lexeme);
+                // a variable without initalizer has a nil literal that wasn't written
+                let span = self.ast.expr_span(*initalizer);
+                if span.start < span.end {
+                    let initalizer = self.expression(*initalizer);
+                    self.push(" ");
+                    self.token(Tokentype::Equal);
+                    self.push(" ");
+                    self.push(&initalizer);
+                }
+                self.token(Tokentype::Semicolon);
+            }
+            Stmt::Block { statements } => self.block(statements, self.ast.stmt_span(id).end),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.token(Tokentype::If);
+                self.condition(*condition);
+                self.branch(*then_branch);
+                if let Some(else_branch) = else_branch {
+                    // comments between the then branch and else stay before else, which goes on a
+                    // line of its own then
+                    let block = matches!(self.ast.stmt(*then_branch), Stmt::Block { .. });
+                    let (written, end) = (self.next, self.ast.stmt_span(*then_branch).end);
+                    if block {
+                        self.trailing_comment(end);
+                    }
+                    let keyword = self.skip_comments(self.last.max(end));
+                    if block && self.next == written && self.next_comment_before(keyword).is_none()
+                    {
+                        self.push(" ");
+                    } else {
+                        self.output.push('\n');
+                        self.comments(keyword);
+                        self.output.push_str(&INDENT.repeat(self.indent));
+                    }
+                    self.token(Tokentype::Else);
+                    if matches!(self.ast.stmt(*else_branch), Stmt::If { .. }) {
+                        self.push(" ");
+                        self.statement_body(*else_branch);
+                    } else {
+                        self.branch(*else_branch);
+                    }
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.token(Tokentype::While);
+                self.condition(*condition);
+                self.branch(*body);
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: ExprId) {
+        let condition = self.expression(condition);
+        let condition = self.glue(&[
+            self.spelling(Tokentype::LeftParen),
+            &condition,
+            self.spelling(Tokentype::RightParen),
+        ]);
+        self.push(" ");
+        self.push(&condition);
+    }
+
+    // a block body stays on the line of its statement, anything else goes on the next one
+    fn branch(&mut self, id: StmtId) {
+        if let Stmt::Block { statements } = self.ast.stmt(id) {
+            self.push(" ");
+            self.block(statements, self.ast.stmt_span(id).end);
+            return;
+        }
+        let span = self.ast.stmt_span(id);
+        self.output.push('\n');
+        self.indent += 1;
+        self.fresh = true;
+        self.comments(self.head_end(id));
+        self.line(span.start);
+        self.statement_body(id);
+        self.trailing_comment(span.end);
+        self.indent -= 1;
+    }
+
+    fn block(&mut self, statements: &[StmtId], end: usize) {
+        let commented = self.next_comment_before(end).is_some();
+        self.token(Tokentype::LeftBrace);
+        if statements.is_empty() && !commented {
+            self.token(Tokentype::RightBrace);
+            return;
+        }
+        self.output.push('\n');
+        self.indent += 1;
+        self.fresh = true;
+        for statement in statements {
+            self.statement(*statement);
+        }
+        self.comments(end);
+        self.indent -= 1;
+        self.output.push_str(&INDENT.repeat(self.indent));
+        self.token(Tokentype::RightBrace);
+        self.fresh = false;
+    }
+
+    fn expression(&self, id: ExprId) -> String {
+        match self.ast.expr(id) {
+            Expr::Literal { value } => match value {
+                Object::True => self.spelling(Tokentype::True).to_string(),
+                Object::False => self.spelling(Tokentype::False).to_string(),
+                Object::Null => self.spelling(Tokentype::Nil).to_string(),
+                _ => {
+                    let span = self.ast.expr_span(id);
+                    self.text[span.start..span.end].to_string()
+                }
+            },
+            Expr::Grouping { expression } => self.glue(&[
+                self.spelling(Tokentype::LeftParen),
+                &self.expression(*expression),
+                self.spelling(Tokentype::RightParen),
+            ]),
+            Expr::Unary { operator, right } => {
+                self.glue(&[self.spelling(operator.tokentype), &self.expression(*right)])
+            }
+            Expr::Binary {
+                left,
+                operator,
+                right,
+            }
+            | Expr::Logical {
+                left,
+                operator,
+                right,
+            } => format!(
+                "{} {} {}",
+                self.expression(*left),
+                self.spelling(operator.tokentype),
+                self.expression(*right)
+            ),
+            Expr::Variable { name } => name.lexeme.to_string(),
+            Expr::Assign { name, value } => format!(
+                "{} {} {}",
+                name.lexeme,
+                self.spelling(Tokentype::Equal),
+                self.expression(*value)
+            ),
+            Expr::Index { object, index, .. } => self.glue(&[
+                &self.expression(*object),
+                self.spelling(Tokentype::LeftBracket),
+                &self.expression(*index),
+                self.spelling(Tokentype::RightBracket),
+            ]),
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                let callee = self.expression(*callee);
+                let arguments: Vec<String> = arguments
+                    .iter()
+                    .map(|argument| self.expression(*argument))
+                    .collect();
+                let mut parts = vec![callee.as_str(), self.spelling(Tokentype::LeftParen)];
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        parts.push(self.spelling(Tokentype::Comma));
+                        parts.push(" ");
+                    }
+                    parts.push(argument);
+                }
+                parts.push(self.spelling(Tokentype::RightParen));
+                self.glue(&parts)
+            }
+            Expr::Get { object, name } => self.glue(&[
+                &self.expression(*object),
+                self.spelling(Tokentype::Dot),
+                &name.lexeme,
+            ]),
+        }
+    }
+
+    // starts a line for what the source has at offset, a blank line before it is kept
+    fn line(&mut self, offset: usize) {
+        let gap = &self.text[self.last.min(offset)..offset];
+        if !self.fresh && gap.matches('\n').count() > 1 {
+            self.output.push('\n');
+        }
+        self.fresh = false;
+        self.output.push_str(&INDENT.repeat(self.indent));
+    }
+
+    // writes the comments before offset on lines of their own
+    fn comments(&mut self, offset: usize) {
+        while let Some(comment) = self.comments.get(self.next) {
+            if comment.start >= offset {
+                break;
+            }
+            let (start, end) = (comment.start, comment.end);
+            self.line(start);
+            self.output.push_str(&self.text[start..end]);
+            self.output.push('\n');
+            self.last = end;
+            self.next += 1;
+        }
+    }
+
+    // where the next token after offset starts, past whitespace and comments
+    fn skip_comments(&self, mut offset: usize) -> usize {
+        let mut next = self.next;
+        loop {
+            let rest = &self.text[offset..];
+            offset += rest.len() - rest.trim_start().len();
+            match self.comments.get(next) {
+                Some(comment) if comment.start == offset => {
+                    offset = comment.end;
+                    next += 1;
+                }
+                _ => return offset,
+            }
+        }
+    }
+
+    fn next_comment_before(&self, offset: usize) -> Option<&Comment> {
+        self.comments
+            .get(self.next)
+            .filter(|comment| comment.start < offset)
+    }
+
+    // a comment on the line a statement ended on stays there
+    fn trailing_comment(&mut self, end: usize) {
+        self.last = self.last.max(end);
+        if let Some(comment) = self.comments.get(self.next) {
+            if comment.start >= end && !self.text[end..comment.start].contains('\n') {
+                self.output.push(' ');
+                self.output.push_str(&self.text[comment.start..comment.end]);
+                self.last = comment.end;
+                self.next += 1;
+            }
+        }
+    }
+
+    // writes the spelling of a keyword or symbol
+    fn token(&mut self, tokentype: Tokentype) {
+        let dialect = self.dialect;
+        self.push(dialect.spelling(tokentype).unwrap_or_default());
+    }
+
+    fn push(&mut self, text: &str) {
+        if needs_space(&self.output, text) {
+            self.output.push(' ');
+        }
+        self.output.push_str(text);
+    }
+
+    // joins parts of an expression, dialects can spell symbols as words
+    fn glue(&self, parts: &[&str]) -> String {
+        let mut glued = String::new();
+        for part in parts {
+            if needs_space(&glued, part) {
+                glued.push(' ');
+            }
+            glued.push_str(part);
+        }
+        glued
+    }
+
+    fn spelling(&self, tokentype: Tokentype) -> &str {
+        self.dialect.spelling(tokentype).unwrap_or_default()
+    }
+}
@@ -1,195 +1,528 @@
 pub mod environment;
+pub mod gc;
+pub mod io;
+pub mod limits;
+mod memory;
+pub mod natives;
+pub mod sandbox;
 
-use std::process;
+use std::{
+    fmt, mem, thread,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    diagnostic::Diagnostic,
+    messages::{Code, Message, Messages},
     object::Object,
     parser::{
-        expr::{bool, is_truthy, Expr},
+        ast::{Ast, ExprId, StmtId},
+        expr::{apply_binary, apply_unary, is_truthy, Expr},
         stmt::Stmt,
     },
-    token::Tokentype,
+    scanner::symbol::Symbol,
+    token::{Token, Tokentype},
 };
 use environment::Environment;
+use gc::{GcStats, Heap, List};
+use io::{Io, StdIo};
+use limits::{InterruptHandle, Limits};
+use memory::Memory;
+use sandbox::Sandbox;
+
+// how often a sleeping script checks whether it should stop
+const NAP: Duration = Duration::from_millis(10);
+
+pub struct RuntimeError {
+    pub token: Box<Token>, // boxed to keep Result<Object, RuntimeError> small
+    pub message: Message,
+}
+
+impl RuntimeError {
+    pub fn new(token: Token, code: Code, args: &[&dyn ToString]) -> Self {
+        RuntimeError::with_message(token, Message::new(code, args))
+    }
+
+    pub fn with_message(token: Token, message: Message) -> Self {
+        RuntimeError {
+            token: Box::new(token),
+            message,
+        }
+    }
+
+    pub fn localize(&mut self, messages: &Messages) {
+        self.message.localize(messages)
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Diagnostic::at_token(&self.token, &self.message))
+    }
+}
 
 pub struct Interpreter {
     environment: Environment,
+    random_state: u64,
+    io: Box<dyn Io>,
+    limits: Limits,
+    // steps and the deadline of the current call to interpret
+    steps: u64,
+    deadline: Option<Instant>,
+    memory: Memory,
+    heap: Heap,
+    // values expressions are still working with, like the arguments of a call, the collector
+    // can't see them otherwise
+    stack: Vec<Object>,
+    interrupt: InterruptHandle,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
+        Interpreter::with_io(Box::new(StdIo))
+    }
+
+    // an interpreter whose print, input and file natives use io instead of the real thing
+    pub fn with_io(io: Box<dyn Io>) -> Self {
+        Interpreter::sandboxed(io, &Sandbox::default())
+    }
+
+    pub fn sandboxed(io: Box<dyn Io>, sandbox: &Sandbox) -> Self {
+        let mut environment = Environment::new();
+        natives::register(&mut environment, sandbox);
         Interpreter {
-            environment: Environment::new(),
+            environment,
+            random_state: natives::math::default_seed(),
+            io,
+            limits: Limits::default(),
+            steps: 0,
+            deadline: None,
+            memory: Memory::default(),
+            heap: Heap::default(),
+            stack: Vec::new(),
+            interrupt: InterruptHandle::default(),
         }
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) {
-        for statement in statements {
-            self.execute_statement(statement);
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    // a handle other threads can stop the running script with, an interrupt that arrives while
+    // nothing runs stops the next script. the interrupt is cleared once a script reports it
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    pub fn define_global(&mut self, name: &str, value: Object) {
+        self.memory.hold(&value);
+        if let Some(old) = self.environment.define(Symbol::new(name), value) {
+            self.memory.release(&old);
         }
     }
+
+    // a list scripts can use, lists made any other way are never collected. the collector only
+    // runs with scripts and only sees their variables, a list kept elsewhere is emptied when a
+    // collection finds no variable that reaches it
+    pub fn list(&mut self, values: Vec<Object>) -> Object {
+        self.heap.list(values)
+    }
+
+    // collects on every allocation, for finding objects freed too early
+    pub fn set_gc_stress(&mut self, stress: bool) {
+        self.heap.stress = stress
+    }
+
+    pub fn gc_stats(&self) -> GcStats {
+        self.heap.stats
+    }
+
+    // bytes held by the variables of scripts, strings and lists count with their contents, once
+    // however many variables share them
+    pub fn memory_usage(&self) -> usize {
+        self.memory.used()
+    }
+
+    // the program stays with the caller, the interpreter only walks it
+    pub fn interpret(&mut self, ast: &Ast) -> Result<(), RuntimeError> {
+        self.steps = 0;
+        self.stack.clear();
+        self.deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+        for statement in &ast.statements {
+            self.execute_statement(ast, *statement)?;
+        }
+        Ok(())
+    }
 }
 
 // for expression
 impl Interpreter {
-    fn visit_expression(&mut self, expression: Expr) -> Object {
-        match expression {
-            Expr::Literal { value } => {
-                let val = value;
-                val.clone()
-            }
-            Expr::Grouping { expression } => self.evaluate_expression(*expression),
+    fn visit_expression(&mut self, ast: &Ast, expression: ExprId) -> Result<Object, RuntimeError> {
+        match ast.expr(expression) {
+            Expr::Literal { value } => Ok(value.clone()),
+            Expr::Grouping { expression } => self.evaluate_expression(ast, *expression),
             Expr::Unary { operator, right } => {
-                let right = self.evaluate_expression(*right);
-
-                match operator.tokentype {
-                    Tokentype::Bang => {
-                        let truthy = is_truthy(&right);
-                        if truthy == Object::True {
-                            Object::False
-                        } else {
-                            Object::True
-                        }
-                    }
-                    Tokentype::Minus => match right {
-                        Object::IntValue(value) => Object::IntValue(-value),
-                        Object::FloatValue(value) => Object::FloatValue(-value),
-                        _ => Object::Null,
-                    },
-                    _ => Object::Null,
-                }
+                let right = self.evaluate_expression(ast, *right)?;
+                apply_unary(operator.tokentype, right)
+                    .map_err(|message| RuntimeError::with_message(operator.clone(), message))
             }
             Expr::Binary {
                 left,
                 operator,
                 right,
             } => {
-                let left = self.evaluate_expression(*left);
-                let right = self.evaluate_expression(*right);
-                // matchception begins here ;) good luck understanding code
-                // changed my mind writing clean code ;)
-                match operator.tokentype {
-                    Tokentype::Minus => left - right,
-                    Tokentype::Plus => left + right,
-                    Tokentype::Slash => left / right,
-                    Tokentype::Star => left * right,
-                    Tokentype::Greater => bool(left > right),
-                    Tokentype::GreaterEqual => bool(left >= right),
-                    Tokentype::Less => bool(left < right),
-                    Tokentype::LessEqual => bool(left <= right),
-                    Tokentype::EqualEqual => bool(left == right),
-                    Tokentype::BangEqual => bool(left != right),
-                    _ => Object::Null,
+                let left = self.evaluate_expression(ast, *left)?;
+                self.stack.push(left.clone());
+                let right = self.evaluate_expression(ast, *right);
+                self.stack.pop();
+                let right = right?;
+                if let (Object::StringValue(lhs), Object::StringValue(rhs)) = (&left, &right) {
+                    self.reserve(lhs.len() + rhs.len())
+                        .map_err(|message| RuntimeError::with_message(operator.clone(), message))?;
                 }
+                apply_binary(operator.tokentype, left, right)
+                    .map_err(|message| RuntimeError::with_message(operator.clone(), message))
             }
-            Expr::Variable { name } => match self.environment.get(name) {
-                Ok(val) => val,
-                Err(msg) => {
-                    println!("Error : {} ", msg);
-                    Object::Null
-                }
-            },
+            Expr::Variable { name } => self.environment.get(name),
             Expr::Assign { name, value } => {
-                let value = self.evaluate_expression(*value);
-                match self.environment.assign(name, &value) {
-                    Ok(_) => {}
-                    Err(msg) => println!("{}", msg),
-                };
-                value
+                let value = self.evaluate_expression(ast, *value)?;
+                let old = self.environment.assign(name, value.clone())?;
+                self.store(name, &value, old)?;
+                Ok(value)
+            }
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate_expression(ast, *object)?;
+                self.stack.push(object.clone());
+                let index = self.evaluate_expression(ast, *index);
+                self.stack.pop();
+                match (object, index?) {
+                    (Object::StringValue(text), Object::IntValue(index)) => {
+                        let character = usize::try_from(index)
+                            .ok()
+                            .and_then(|i| text.chars().nth(i));
+                        match character {
+                            Some(character) => {
+                                Ok(Object::StringValue(character.to_string().into()))
+                            }
+                            None => Err(RuntimeError::new(
+                                bracket.clone(),
+                                Code::StringIndexOutOfRange,
+                                &[&index, &text.chars().count()],
+                            )),
+                        }
+                    }
+                    (Object::List(values), Object::IntValue(index)) => {
+                        let values = values.borrow();
+                        match usize::try_from(index).ok().and_then(|i| values.get(i)) {
+                            Some(value) => Ok(value.clone()),
+                            None => Err(RuntimeError::new(
+                                bracket.clone(),
+                                Code::IndexOutOfRange,
+                                &[&index, &values.len()],
+                            )),
+                        }
+                    }
+                    _ => Err(RuntimeError::new(bracket.clone(), Code::NotIndexable, &[])),
+                }
+            }
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                // the arguments stay on the stack until the call returns
+                let base = self.stack.len();
+                let result = self.call_expression(ast, *callee, paren, arguments);
+                self.stack.truncate(base);
+                result
             }
+            Expr::Get { name, .. } => Err(RuntimeError::new(
+                name.clone(),
+                Code::MethodNotCalled,
+                &[&name.lexeme],
+            )),
             Expr::Logical {
                 left,
                 operator,
                 right,
             } => {
-                let left = self.evaluate_expression(*left);
+                let left = self.evaluate_expression(ast, *left)?;
 
                 if operator.tokentype == Tokentype::Or {
                     if is_truthy(&left) == Object::True {
-                        left
+                        Ok(left)
                     } else {
-                        self.evaluate_expression(*right)
+                        self.evaluate_expression(ast, *right)
                     }
+                } else if !(is_truthy(&left) == Object::True) {
+                    Ok(left)
                 } else {
-                    if !(is_truthy(&left) == Object::True) {
-                        left
-                    } else {
-                        self.evaluate_expression(*right)
+                    self.evaluate_expression(ast, *right)
+                }
+            }
+        }
+    }
+
+    fn evaluate_expression(&mut self, ast: &Ast, expr: ExprId) -> Result<Object, RuntimeError> {
+        self.visit_expression(ast, expr)
+    }
+
+    // a method call passes the object it is called on as the first argument
+    fn call_expression(
+        &mut self,
+        ast: &Ast,
+        callee: ExprId,
+        paren: &Token,
+        arguments: &[ExprId],
+    ) -> Result<Object, RuntimeError> {
+        let base = self.stack.len();
+        let callee = match ast.expr(callee) {
+            Expr::Get { object, name } => {
+                let object = self.evaluate_expression(ast, *object)?;
+                match natives::method(&name.lexeme) {
+                    Some(native) => {
+                        self.stack.push(object);
+                        Object::Native(native)
+                    }
+                    None => {
+                        return Err(RuntimeError::new(
+                            name.clone(),
+                            Code::NotAMethod,
+                            &[&name.lexeme, &object.type_name()],
+                        ));
                     }
                 }
             }
+            _ => self.evaluate_expression(ast, callee)?,
+        };
+        for argument in arguments {
+            let value = self.evaluate_expression(ast, *argument)?;
+            self.stack.push(value);
         }
+        let values = self.stack[base..].to_vec();
+        self.call(callee, values, paren)
     }
 
-    fn evaluate_expression(&mut self, expr: Expr) -> Object {
-        self.visit_expression(expr)
+    // errors of the callee are reported at the closing parenthesis of the call
+    fn call(
+        &mut self,
+        callee: Object,
+        arguments: Vec<Object>,
+        paren: &Token,
+    ) -> Result<Object, RuntimeError> {
+        self.check_limits(paren)?;
+        match callee {
+            Object::Native(native) => {
+                if let Some(capability) = native.denied {
+                    return Err(RuntimeError::new(
+                        paren.clone(),
+                        Code::PermissionDenied,
+                        &[&capability, &native.name],
+                    ));
+                }
+                if arguments.len() < native.min || arguments.len() > native.max {
+                    return Err(RuntimeError::new(
+                        paren.clone(),
+                        Code::WrongArity,
+                        &[&native.name, &native.arity(), &arguments.len()],
+                    ));
+                }
+                let result = (native.function)(self, arguments).and_then(|value| {
+                    // natives only check what they could blow up, the rest is checked here. the
+                    // value goes on the stack of the call so a collection doesn't empty it
+                    self.stack.push(value.clone());
+                    self.reserve(self.memory.cost(&value)).map(|_| value)
+                });
+                result.map_err(|message| RuntimeError::with_message(paren.clone(), message))
+            }
+            _ => Err(RuntimeError::new(paren.clone(), Code::NotCallable, &[])),
+        }
     }
 }
 
 // for statements
 impl Interpreter {
-    fn visit_statement(&mut self, statement: Stmt) {
-        match statement {
+    fn visit_statement(&mut self, ast: &Ast, statement: StmtId) -> Result<(), RuntimeError> {
+        match ast.stmt(statement) {
             Stmt::Expression { expression } => {
-                let _ = self.evaluate_expression(expression);
-            }
-            Stmt::Print { expression } => {
-                let val = self.evaluate_expression(expression);
-                println!("{}", val);
-            }
-            Stmt::Var { name, initalizer } => {
-                let mut value: Object = Object::Null;
-                let null = Expr::Literal {
-                    value: Object::Null,
-                };
-                if initalizer != null {
-                    value = self.evaluate_expression(initalizer);
+                let _ = self.evaluate_expression(ast, *expression)?;
+            }
+            Stmt::Print {
+                keyword,
+                expression,
+            } => {
+                self.check_limits(keyword)?;
+                let val = self.evaluate_expression(ast, *expression)?;
+                if let Err(err) = self.io.print(&val.to_string()) {
+                    return Err(RuntimeError::new(
+                        keyword.clone(),
+                        Code::IoFailed,
+                        &[&"print", &err],
+                    ));
                 }
-
-                self.environment.define(name.lexeme, value)
             }
-            Stmt::Block { statements } => self.execute_block(statements, self.environment.clone()),
+            Stmt::Var {
+                name, initalizer, ..
+            } => {
+                self.check_limits(name)?;
+                // a variable without initalizer has a nil literal
+                let value = self.evaluate_expression(ast, *initalizer)?;
+                let old = self.environment.define(name.lexeme.clone(), value.clone());
+                self.store(name, &value, old.unwrap_or(Object::Null))?;
+            }
+            Stmt::Block { statements } => self.execute_block(ast, statements)?,
             Stmt::If {
                 condition,
                 then_branch,
                 else_branch,
             } => {
-                if self.evaluate_expression(condition) == Object::True {
-                    self.execute_statement(*then_branch);
-                } else {
-                    match *else_branch {
-                        Some(statement) => {
-                            self.execute_statement(statement);
-                        }
-                        None => (),
-                    }
+                if self.evaluate_expression(ast, *condition)? == Object::True {
+                    self.execute_statement(ast, *then_branch)?;
+                } else if let Some(statement) = else_branch {
+                    self.execute_statement(ast, *statement)?;
                 }
             }
-            Stmt::While { condition, body } => {
-                while self.evaluate_expression(condition.clone()) == Object::True {
-                    self.execute_statement(*body.clone());
+            Stmt::While {
+                keyword,
+                condition,
+                body,
+            } => {
+                while self.evaluate_expression(ast, *condition)? == Object::True {
+                    // an empty body is no statement, the iteration still counts
+                    self.steps += 1;
+                    self.check_limits(keyword)?;
+                    self.execute_statement(ast, *body)?;
                 }
             }
         }
+        Ok(())
+    }
+
+    fn execute_statement(&mut self, ast: &Ast, statement: StmtId) -> Result<(), RuntimeError> {
+        self.steps += 1;
+        self.visit_statement(ast, statement)
+    }
+
+    // fails when bytes more would not fit in the memory limit even after collecting garbage, so
+    // lists the caller works with have to be in a variable or on the stack
+    fn reserve(&mut self, bytes: usize) -> Result<(), Message> {
+        let Some(memory) = self.limits.memory else {
+            return Ok(());
+        };
+        if self.memory.used().saturating_add(bytes) > memory {
+            self.collect(&[]);
+        }
+        if self.memory.used().saturating_add(bytes) > memory {
+            return Err(Message::new(Code::MemoryLimit, &[&memory]));
+        }
+        Ok(())
+    }
+
+    // counts a variable at token now holding value instead of old
+    fn store(&mut self, token: &Token, value: &Object, old: Object) -> Result<(), RuntimeError> {
+        self.memory.hold(value);
+        self.memory.release(&old);
+        self.reserve(0)
+            .map_err(|message| RuntimeError::with_message(token.clone(), message))
     }
 
-    fn execute_statement(&mut self, statement: Stmt) {
-        self.visit_statement(statement);
+    // a list made by a native, the heap is collected first once it has grown
+    fn allocate(&mut self, values: Vec<Object>) -> Object {
+        if self.heap.due() {
+            self.collect(&values);
+        }
+        self.heap.list(values)
+    }
+
+    // frees the lists only cycles keep alive, values are roots besides the variables and the
+    // stack. returns the bytes freed
+    fn collect(&mut self, values: &[Object]) -> u64 {
+        let freed = self.heap.stats.freed_bytes;
+        let roots = self.environment.values().chain(&self.stack).chain(values);
+        let garbage = self.heap.collect(roots);
+        self.memory.forget(&garbage);
+        self.heap.stats.freed_bytes - freed
+    }
+
+    // adds value to the end of list, counted when variables hold the list
+    fn push(&mut self, list: &List, value: Object) -> Result<(), Message> {
+        if self.memory.holds(list) {
+            self.reserve(mem::size_of::<Object>() + self.memory.cost(&value))?;
+        }
+        list.borrow_mut().push(value.clone());
+        self.memory.grow(list, &value);
+        Ok(())
     }
 
-    fn execute_block(&mut self, statements: Vec<Stmt>, environment: Environment) {
-        self.environment = Environment::new_with_enclosing(environment.clone());
+    // stops the script at token once it went over its budget or was interrupted
+    fn check_limits(&mut self, token: &Token) -> Result<(), RuntimeError> {
+        self.check_budget()
+            .map_err(|message| RuntimeError::with_message(token.clone(), message))
+    }
 
-        for statement in statements {
-            self.execute_statement(statement);
+    fn check_budget(&mut self) -> Result<(), Message> {
+        if self.interrupt.is_interrupted() {
+            self.interrupt.reset();
+            return Err(Message::new(Code::Interrupted, &[]));
+        }
+        if let Some(steps) = self.limits.steps {
+            if self.steps > steps {
+                return Err(Message::new(Code::StepLimit, &[&steps]));
+            }
         }
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.limits.timeout) {
+            if Instant::now() >= deadline {
+                let timeout = format!("{:?}", timeout);
+                return Err(Message::new(Code::TimeLimit, &[&timeout]));
+            }
+        }
+        Ok(())
+    }
 
-        match self.environment.get_enclosing() {
-            Some(environment) => self.environment = environment,
-            None => {
-                println!("Environment Lost . ");
-                process::exit(64);
+    // sleeps in short naps so the deadline and interrupts still stop the script
+    fn sleep(&mut self, duration: Duration) -> Result<(), Message> {
+        // too long to add to now is forever
+        let end = Instant::now().checked_add(duration);
+        loop {
+            self.check_budget()?;
+            let now = Instant::now();
+            let mut nap = match end {
+                Some(end) if now >= end => return Ok(()),
+                Some(end) => (end - now).min(NAP),
+                None => NAP,
+            };
+            if let Some(deadline) = self.deadline {
+                nap = nap.min(deadline.saturating_duration_since(now));
             }
-        };
+            thread::sleep(nap);
+        }
+    }
+
+    fn execute_block(&mut self, ast: &Ast, statements: &[StmtId]) -> Result<(), RuntimeError> {
+        self.environment.push_scope();
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute_statement(ast, *statement));
+
+        // the scope is popped even if the block failed
+        for value in self.environment.pop_scope() {
+            self.memory.release(&value);
+        }
+        result
     }
 }
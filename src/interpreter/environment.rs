@@ -1,61 +1,83 @@
 use std::collections::HashMap;
 
-use crate::{object::Object, token::Token};
+use super::RuntimeError;
+use crate::{messages::Code, object::Object, scanner::symbol::Symbol, token::Token};
 
-#[derive(Clone)]
+// scopes from the globals to the innermost block, a block pushes its own scope and pops it again
+// without copying the ones around it
 pub struct Environment {
-    enclosing: Box<Option<Environment>>,
-    values: HashMap<String, Object>,
+    scopes: Vec<HashMap<Symbol, Object>>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Environment {
     pub fn new() -> Self {
         Environment {
-            values: HashMap::new(),
-            enclosing: Box::new(Option::None),
+            scopes: vec![HashMap::new()],
         }
     }
 
-    pub fn new_with_enclosing(environment: Environment) -> Self {
-        Environment {
-            values: HashMap::new(),
-            enclosing: Box::new(Option::Some(environment)),
-        }
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new())
     }
 
-    pub fn get_enclosing(&mut self) -> Option<Environment> {
-        *self.enclosing.clone()
+    // the globals are never popped, returns the values of the popped scope
+    pub fn pop_scope(&mut self) -> Vec<Object> {
+        if self.scopes.len() > 1 {
+            if let Some(scope) = self.scopes.pop() {
+                return scope.into_values().collect();
+            }
+        }
+        Vec::new()
     }
 
-    pub fn _add_enclosing(&mut self, environment: Environment) {
-        self.enclosing = Box::new(Option::Some(environment));
+    //not checking if variable already exists thus allowing reinitalization of a variable
+    // returns the value it had before
+    pub fn define(&mut self, name: Symbol, value: Object) -> Option<Object> {
+        self.innermost().insert(name, value)
     }
 
-    //not checking if variable already exists thus allowing reinitalization of a variable
-    pub fn define(&mut self, name: String, value: Object) {
-        // println!("defining variable");
-        self.values.insert(name, value);
+    // the values of every variable in scope, the roots of the collector
+    pub fn values(&self) -> impl Iterator<Item = &Object> {
+        self.scopes.iter().flat_map(|scope| scope.values())
     }
 
-    pub fn get(&self, name: Token) -> Result<Object, &str> {
-        match self.values.get(&name.lexeme) {
+    pub fn get(&self, name: &Token) -> Result<Object, RuntimeError> {
+        match self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme))
+        {
             Some(val) => Ok(val.clone()),
-            None => match &*self.enclosing {
-                Some(env) => env.get(name),
-                None => Err("Undefined variable"),
-            },
+            None => Err(undefined(name)),
         }
     }
 
-    pub fn assign(&mut self, name: Token, value: &Object) -> Result<(), String> {
-        if self.values.contains_key(&name.lexeme) {
-            *self.values.entry(name.lexeme).or_insert(Object::Null) = value.clone();
-            Ok(())
-        } else {
-            match &mut *self.enclosing {
-                Some(environment) => environment.assign(name, value),
-                None => Err("Error : Undefined variable ".to_string() + &name.lexeme),
-            }
+    // returns the value the variable had before
+    pub fn assign(&mut self, name: &Token, value: Object) -> Result<Object, RuntimeError> {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&name.lexeme))
+        {
+            Some(old) => Ok(std::mem::replace(old, value)),
+            None => Err(undefined(name)),
         }
     }
+
+    fn innermost(&mut self) -> &mut HashMap<Symbol, Object> {
+        let last = self.scopes.len() - 1;
+        &mut self.scopes[last]
+    }
+}
+
+fn undefined(name: &Token) -> RuntimeError {
+    RuntimeError::new(name.clone(), Code::UndefinedVariable, &[&name.lexeme])
 }
@@ -0,0 +1,114 @@
+use crate::object::Object;
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt, mem,
+    rc::{Rc, Weak},
+};
+
+// lists live on the heap and are shared by every value pointing at them
+pub type List = Rc<RefCell<Vec<Object>>>;
+
+// a heap this small is never collected on its own
+const MIN_THRESHOLD: usize = 256;
+
+#[derive(Clone, Copy, Default)]
+pub struct GcStats {
+    pub collections: u64,
+    pub freed_objects: u64,
+    pub freed_bytes: u64,
+}
+
+impl fmt::Display for GcStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gc: {} collections, {} objects freed, {} bytes freed",
+            self.collections, self.freed_objects, self.freed_bytes
+        )
+    }
+}
+
+// reference counting frees whatever nothing points to, the collector frees what only cycles keep
+// alive. it marks the lists its roots reach, the interpreter hands it the variables of every
+// scope and the values expressions are still working with, and empties the rest
+pub struct Heap {
+    objects: Vec<Weak<RefCell<Vec<Object>>>>,
+    // collect once this many objects are tracked
+    threshold: usize,
+    // collect on every allocation, to shake out objects that are freed too early
+    pub stress: bool,
+    pub stats: GcStats,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Heap {
+            objects: Vec::new(),
+            threshold: MIN_THRESHOLD,
+            stress: false,
+            stats: GcStats::default(),
+        }
+    }
+}
+
+impl Heap {
+    pub fn list(&mut self, values: Vec<Object>) -> Object {
+        let list = Rc::new(RefCell::new(values));
+        self.objects.push(Rc::downgrade(&list));
+        Object::List(list)
+    }
+
+    // whether the next allocation should collect first
+    pub fn due(&self) -> bool {
+        self.stress || self.objects.len() >= self.threshold
+    }
+
+    // empties the lists roots can't reach, which breaks the cycles keeping them alive. they are
+    // returned with the values they held
+    pub fn collect<'a>(
+        &mut self,
+        roots: impl Iterator<Item = &'a Object>,
+    ) -> Vec<(List, Vec<Object>)> {
+        let mut marked = HashSet::new();
+        let mut pending: Vec<List> = children(roots).cloned().collect();
+        while let Some(list) = pending.pop() {
+            if marked.insert(Rc::as_ptr(&list)) {
+                pending.extend(children(list.borrow().iter()).cloned());
+            }
+        }
+
+        let mut garbage = Vec::new();
+        for list in self.objects.iter().filter_map(Weak::upgrade) {
+            if marked.contains(&Rc::as_ptr(&list)) {
+                continue;
+            }
+            let values = mem::take(&mut *list.borrow_mut());
+            self.stats.freed_bytes += values
+                .iter()
+                .map(|value| match value {
+                    Object::StringValue(text) => mem::size_of::<Object>() + text.len(),
+                    _ => mem::size_of::<Object>(),
+                })
+                .sum::<usize>() as u64;
+            self.stats.freed_objects += 1;
+            garbage.push((list, values));
+        }
+
+        // the garbage is freed once the caller drops it
+        self.objects.retain(|list| {
+            list.upgrade()
+                .is_some_and(|list| marked.contains(&Rc::as_ptr(&list)))
+        });
+        self.threshold = MIN_THRESHOLD.max(self.objects.len() * 2);
+        self.stats.collections += 1;
+        garbage
+    }
+}
+
+fn children<'a>(values: impl Iterator<Item = &'a Object>) -> impl Iterator<Item = &'a List> {
+    values.filter_map(|value| match value {
+        Object::List(list) => Some(list),
+        _ => None,
+    })
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+};
+
+// everything a script can do to the outside world goes through this, embedders can hand the
+// interpreter their own implementation to keep scripts in memory or away from the disk
+pub trait Io {
+    // what print writes
+    fn print(&mut self, text: &str) -> io::Result<()>;
+    // a line of input without its line ending, None at the end of input
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>>;
+    fn read_file(&mut self, path: &str) -> io::Result<String>;
+    fn write_file(&mut self, path: &str, contents: &str) -> io::Result<()>;
+    fn append_file(&mut self, path: &str, contents: &str) -> io::Result<()>;
+    fn file_exists(&mut self, path: &str) -> io::Result<bool>;
+    // names of the entries of a directory
+    fn list_dir(&mut self, path: &str) -> io::Result<Vec<String>>;
+}
+
+// the terminal and the real file system
+pub struct StdIo;
+
+impl Io for StdIo {
+    fn print(&mut self, text: &str) -> io::Result<()> {
+        writeln!(io::stdout(), "{}", text)
+    }
+
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let len = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(len);
+        Ok(Some(line))
+    }
+
+    fn read_file(&mut self, path: &str) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn write_file(&mut self, path: &str, contents: &str) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn append_file(&mut self, path: &str, contents: &str) -> io::Result<()> {
+        fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?
+            .write_all(contents.as_bytes())
+    }
+
+    fn file_exists(&mut self, path: &str) -> io::Result<bool> {
+        fs::exists(path)
+    }
+
+    fn list_dir(&mut self, path: &str) -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(path)? {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    }
+}
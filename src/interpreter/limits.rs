@@ -0,0 +1,52 @@
+use crate::parser::MAX_NESTING;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+// how much a script may do before it is stopped, checked on every loop iteration, call, print
+// and variable declaration, a script can't run for long without doing one of those
+pub struct Limits {
+    // steps are executed statements and loop iterations
+    pub steps: Option<u64>,
+    // wall-clock time for each call to Interpreter::interpret
+    pub timeout: Option<Duration>,
+    // bytes variables can hold, see Interpreter::memory_usage
+    pub memory: Option<usize>,
+    // how deep statements and expressions of a script can nest, checked by the parser. each
+    // level takes stack when the script runs, the default fits the 8 MiB stack of a main thread
+    // in a debug build, threads with smaller stacks need a lower limit
+    pub nesting: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            steps: None,
+            timeout: None,
+            memory: None,
+            nesting: MAX_NESTING,
+        }
+    }
+}
+
+// stops a running script from another thread, it fails with an interrupted error
+#[derive(Clone, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,132 @@
+use super::gc::List;
+use crate::object::Object;
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    rc::Rc,
+};
+
+// what the memory limit counts, every string and list the variables of a script can reach. a
+// string or list shared by many variables or lists is counted once, for as long as one of them
+// still holds it
+#[derive(Default)]
+pub struct Memory {
+    used: usize,
+    // keyed by the address of the string or list
+    held: HashMap<usize, Held>,
+}
+
+struct Held {
+    bytes: usize,
+    // variables and lists holding it, the values of a list are held by the list once
+    holders: usize,
+}
+
+impl Memory {
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    // bytes holding value would add to what is used
+    pub fn cost(&self, value: &Object) -> usize {
+        let mut seen = HashSet::new();
+        let mut pending = vec![value.clone()];
+        let mut bytes = 0;
+        while let Some(value) = pending.pop() {
+            match key(&value) {
+                Some(key) if !self.held.contains_key(&key) && seen.insert(key) => {
+                    bytes += size(&value);
+                    if let Object::List(list) = &value {
+                        pending.extend(list.borrow().iter().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+        bytes
+    }
+
+    pub fn hold(&mut self, value: &Object) {
+        let mut pending = vec![value.clone()];
+        while let Some(value) = pending.pop() {
+            let Some(key) = key(&value) else { continue };
+            match self.held.get_mut(&key) {
+                Some(held) => held.holders += 1,
+                None => {
+                    let bytes = size(&value);
+                    self.held.insert(key, Held { bytes, holders: 1 });
+                    self.used += bytes;
+                    if let Object::List(list) = &value {
+                        pending.extend(list.borrow().iter().cloned());
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn release(&mut self, value: &Object) {
+        let mut pending = vec![value.clone()];
+        while let Some(value) = pending.pop() {
+            let Some(key) = key(&value) else { continue };
+            let Some(held) = self.held.get_mut(&key) else {
+                continue;
+            };
+            held.holders -= 1;
+            if held.holders == 0 {
+                self.used -= held.bytes;
+                self.held.remove(&key);
+                if let Object::List(list) = &value {
+                    pending.extend(list.borrow().iter().cloned());
+                }
+            }
+        }
+    }
+
+    pub fn holds(&self, list: &List) -> bool {
+        self.held.contains_key(&address(list))
+    }
+
+    // value was added to list, it is counted if the list is
+    pub fn grow(&mut self, list: &List, value: &Object) {
+        if let Some(held) = self.held.get_mut(&address(list)) {
+            held.bytes += mem::size_of::<Object>();
+            self.used += mem::size_of::<Object>();
+            self.hold(value);
+        }
+    }
+
+    // stops counting the lists the collector emptied, only the garbage held them so they go
+    // before what they held is released
+    pub fn forget(&mut self, garbage: &[(List, Vec<Object>)]) {
+        for (list, _) in garbage {
+            if let Some(held) = self.held.remove(&address(list)) {
+                self.used -= held.bytes;
+            }
+        }
+        for value in garbage.iter().flat_map(|(_, values)| values) {
+            self.release(value);
+        }
+    }
+}
+
+fn address(list: &List) -> usize {
+    Rc::as_ptr(list) as *const u8 as usize
+}
+
+// the address of what a value points to, values that point to nothing cost nothing
+fn key(value: &Object) -> Option<usize> {
+    match value {
+        Object::StringValue(text) => Some(Rc::as_ptr(text) as *const u8 as usize),
+        Object::List(list) => Some(address(list)),
+        _ => None,
+    }
+}
+
+// a list counts a slot for each of its values, what they point to is counted on its own
+fn size(value: &Object) -> usize {
+    match value {
+        Object::StringValue(text) => text.len(),
+        Object::List(list) => list.borrow().len() * mem::size_of::<Object>(),
+        _ => 0,
+    }
+}
@@ -0,0 +1,125 @@
+pub mod convert;
+pub mod files;
+pub mod lists;
+pub mod math;
+pub mod strings;
+pub mod system;
+
+use super::{
+    environment::Environment,
+    sandbox::{Capability, Sandbox},
+    Interpreter,
+};
+use crate::{
+    messages::{Code, Message},
+    object::Object,
+    scanner::symbol::Symbol,
+};
+
+// a function implemented in rust, arguments are checked against min and max before it is called
+#[derive(Clone, Copy)]
+pub struct Native {
+    pub name: &'static str,
+    pub min: usize,
+    pub max: usize,
+    pub function: fn(&mut Interpreter, Vec<Object>) -> Result<Object, Message>,
+    // set when the sandbox doesn't allow the capability the native needs
+    pub denied: Option<Capability>,
+}
+
+pub const fn native(
+    name: &'static str,
+    min: usize,
+    max: usize,
+    function: fn(&mut Interpreter, Vec<Object>) -> Result<Object, Message>,
+) -> Native {
+    Native {
+        name,
+        min,
+        max,
+        function,
+        denied: None,
+    }
+}
+
+impl Native {
+    // how many arguments the function takes, for error messages
+    pub fn arity(&self) -> String {
+        if self.min == self.max {
+            self.min.to_string()
+        } else if self.max == usize::MAX {
+            format!("at least {}", self.min)
+        } else {
+            format!("{} to {}", self.min, self.max)
+        }
+    }
+}
+
+// the natives that can also be called as methods, text.upper() is upper(text)
+pub fn method(name: &str) -> Option<Native> {
+    strings::NATIVES
+        .iter()
+        .chain(lists::NATIVES.iter())
+        .find(|native| native.name == name)
+        .copied()
+}
+
+// natives the sandbox doesn't allow are defined anyway so calling them explains why they fail
+pub fn register(environment: &mut Environment, sandbox: &Sandbox) {
+    let groups: [(&[Native], Option<Capability>); 9] = [
+        (&math::NATIVES, None),
+        (&strings::NATIVES, None),
+        (&lists::NATIVES, None),
+        (&convert::NATIVES, None),
+        (&files::NATIVES, Some(Capability::Fs)),
+        (&system::ENV, Some(Capability::Env)),
+        (&system::TIME, Some(Capability::Time)),
+        (&system::PROCESS, Some(Capability::Process)),
+        (&system::GC, None),
+    ];
+    for (natives, capability) in groups {
+        let denied = capability.filter(|capability| !sandbox.allows(*capability));
+        for native in natives {
+            let native = Native { denied, ..*native };
+            environment.define(Symbol::new(native.name), Object::Native(native));
+        }
+    }
+    for (name, value) in math::CONSTANTS.iter() {
+        environment.define(Symbol::new(name), value.clone());
+    }
+}
+
+// argument conversions shared by natives, ints are accepted wherever a float is
+pub fn int(name: &str, value: &Object) -> Result<i64, Message> {
+    value._int().map_err(|_| wrong_type(name, "an int", value))
+}
+
+pub fn float(name: &str, value: &Object) -> Result<f64, Message> {
+    value
+        ._float()
+        .map_err(|_| wrong_type(name, "a number", value))
+}
+
+// a float with no fraction left as an int, name is the native reported when it doesn't fit
+pub fn whole(name: &str, value: f64) -> Result<i64, Message> {
+    // i64::MAX as f64 rounds up to 2^63 which is already out of range
+    if value.is_finite() && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+        Ok(value as i64)
+    } else {
+        Err(Message::new(Code::IntOverflow, &[&name]))
+    }
+}
+
+pub fn string<'a>(name: &str, value: &'a Object) -> Result<&'a str, Message> {
+    match value {
+        Object::StringValue(text) => Ok(text),
+        value => Err(wrong_type(name, "a string", value)),
+    }
+}
+
+pub fn wrong_type(name: &str, expected: &str, value: &Object) -> Message {
+    Message::new(
+        Code::WrongArgumentType,
+        &[&name, &expected, &value.type_name()],
+    )
+}
@@ -0,0 +1,56 @@
+use super::{native, string, whole, wrong_type, Native};
+use crate::{
+    interpreter::Interpreter,
+    messages::{Code, Message},
+    object::Object,
+    parser::expr::is_truthy,
+    scanner::parse_literal,
+};
+
+pub const NATIVES: [Native; 6] = [
+    native("str", 1, 1, |_, args| {
+        Ok(Object::StringValue(args[0].to_string().into()))
+    }),
+    native("int", 1, 1, int),
+    native("float", 1, 1, float),
+    native("bool", 1, 1, |_, args| Ok(is_truthy(&args[0]))),
+    native("type", 1, 1, |_, args| {
+        Ok(Object::StringValue(args[0].type_name().into()))
+    }),
+    native("parse_number", 1, 1, parse_number),
+];
+
+// floats are truncated toward zero, int(3.7) is 3 and int(-3.7) is -3
+fn int(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    match number("int", &args[0])? {
+        Object::FloatValue(value) => Ok(Object::IntValue(whole("int", value.trunc())?)),
+        value => Ok(value),
+    }
+}
+
+fn float(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    match number("float", &args[0])? {
+        Object::IntValue(value) => Ok(Object::FloatValue(value as f64)),
+        value => Ok(value),
+    }
+}
+
+// numbers stay as they are, bools are 1 and 0 and strings are read like number literals
+fn number(name: &str, value: &Object) -> Result<Object, Message> {
+    match value {
+        Object::IntValue(_) | Object::FloatValue(_) => Ok(value.clone()),
+        Object::True => Ok(Object::IntValue(1)),
+        Object::False => Ok(Object::IntValue(0)),
+        Object::StringValue(text) => parse(text),
+        value => Err(wrong_type(name, "a number, a string or a bool", value)),
+    }
+}
+
+fn parse_number(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    parse(string("parse_number", &args[0])?)
+}
+
+// accepts everything a number literal can be, like 42, -3.5, 1_000, 1e9 or 0xFF
+fn parse(text: &str) -> Result<Object, Message> {
+    parse_literal(text.trim()).ok_or_else(|| Message::new(Code::NotANumber, &[&text]))
+}
@@ -0,0 +1,61 @@
+use super::{native, string, Native};
+use crate::{
+    messages::{Code, Message},
+    object::Object,
+    parser::expr::bool,
+};
+use std::io;
+
+// files, everything goes through the Io of the interpreter
+pub const NATIVES: [Native; 6] = [
+    native("read_file", 1, 1, |interpreter, args| {
+        let path = string("read_file", &args[0])?;
+        let text = interpreter.io.read_file(path);
+        Ok(Object::StringValue(failed("read_file", path, text)?.into()))
+    }),
+    native("write_file", 2, 2, |interpreter, args| {
+        let (path, text) = (
+            string("write_file", &args[0])?,
+            string("write_file", &args[1])?,
+        );
+        let result = interpreter.io.write_file(path, text);
+        failed("write_file", path, result)?;
+        Ok(Object::Null)
+    }),
+    native("append_file", 2, 2, |interpreter, args| {
+        let (path, text) = (
+            string("append_file", &args[0])?,
+            string("append_file", &args[1])?,
+        );
+        let result = interpreter.io.append_file(path, text);
+        failed("append_file", path, result)?;
+        Ok(Object::Null)
+    }),
+    native("file_exists", 1, 1, |interpreter, args| {
+        let path = string("file_exists", &args[0])?;
+        let exists = interpreter.io.file_exists(path);
+        Ok(bool(failed("file_exists", path, exists)?))
+    }),
+    native("read_lines", 1, 1, |interpreter, args| {
+        let path = string("read_lines", &args[0])?;
+        let text = interpreter.io.read_file(path);
+        let lines = failed("read_lines", path, text)?
+            .lines()
+            .map(|line| Object::StringValue(line.into()))
+            .collect();
+        Ok(interpreter.allocate(lines))
+    }),
+    native("list_dir", 1, 1, |interpreter, args| {
+        let path = string("list_dir", &args[0])?;
+        let names = interpreter.io.list_dir(path);
+        let names = failed("list_dir", path, names)?
+            .into_iter()
+            .map(|name| Object::StringValue(name.into()))
+            .collect();
+        Ok(interpreter.allocate(names))
+    }),
+];
+
+fn failed<T>(name: &str, path: &str, result: io::Result<T>) -> Result<T, Message> {
+    result.map_err(|err| Message::new(Code::FileFailed, &[&name, &path, &err]))
+}
@@ -0,0 +1,20 @@
+use super::{native, wrong_type, Native};
+use crate::{interpreter::Interpreter, messages::Message, object::Object};
+
+// lists can hold any value, themselves too, so they can form cycles only the collector frees
+pub const NATIVES: [Native; 2] = [
+    native("list", 0, usize::MAX, |interpreter, args| {
+        Ok(interpreter.allocate(args))
+    }),
+    native("push", 2, 2, push),
+];
+
+// adds a value to the end of a list, list.push(value) is the same as push(list, value)
+fn push(interpreter: &mut Interpreter, mut args: Vec<Object>) -> Result<Object, Message> {
+    let value = args.pop().unwrap_or(Object::Null);
+    match &args[0] {
+        Object::List(list) => interpreter.push(list, value)?,
+        value => return Err(wrong_type("push", "a list", value)),
+    }
+    Ok(Object::Null)
+}
@@ -0,0 +1,202 @@
+use super::{float, int, native, whole, Native};
+use crate::{
+    interpreter::Interpreter,
+    messages::{Code, Message},
+    object::Object,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CONSTANTS: [(&str, Object); 2] = [
+    ("PI", Object::FloatValue(std::f64::consts::PI)),
+    ("E", Object::FloatValue(std::f64::consts::E)),
+];
+
+pub const NATIVES: [Native; 16] = [
+    native("sqrt", 1, 1, sqrt),
+    native("pow", 2, 2, pow),
+    native("abs", 1, 1, abs),
+    native("floor", 1, 1, floor),
+    native("ceil", 1, 1, ceil),
+    native("round", 1, 1, round),
+    native("min", 1, usize::MAX, min),
+    native("max", 1, usize::MAX, max),
+    native("sin", 1, 1, |_, args| {
+        Ok(Object::FloatValue(float("sin", &args[0])?.sin()))
+    }),
+    native("cos", 1, 1, |_, args| {
+        Ok(Object::FloatValue(float("cos", &args[0])?.cos()))
+    }),
+    native("tan", 1, 1, |_, args| {
+        Ok(Object::FloatValue(float("tan", &args[0])?.tan()))
+    }),
+    native("log", 1, 2, log),
+    native("exp", 1, 1, |_, args| {
+        Ok(Object::FloatValue(float("exp", &args[0])?.exp()))
+    }),
+    native("random", 0, 0, random),
+    native("randint", 2, 2, randint),
+    native("seed", 1, 1, seed),
+];
+
+fn sqrt(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let value = float("sqrt", &args[0])?;
+    if value < 0.0 {
+        return Err(undefined("sqrt", &args[0]));
+    }
+    Ok(Object::FloatValue(value.sqrt()))
+}
+
+// int to a non negative int power stays an int, everything else is a float
+fn pow(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    match (&args[0], &args[1]) {
+        (Object::IntValue(base), Object::IntValue(exponent)) if *exponent >= 0 => {
+            u32::try_from(*exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+                .map(Object::IntValue)
+                .ok_or_else(|| overflow("pow"))
+        }
+        (base, exponent) => Ok(Object::FloatValue(
+            float("pow", base)?.powf(float("pow", exponent)?),
+        )),
+    }
+}
+
+fn abs(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    match &args[0] {
+        Object::IntValue(value) => value
+            .checked_abs()
+            .map(Object::IntValue)
+            .ok_or_else(|| overflow("abs")),
+        value => Ok(Object::FloatValue(float("abs", value)?.abs())),
+    }
+}
+
+fn floor(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    to_int("floor", &args[0], f64::floor)
+}
+
+fn ceil(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    to_int("ceil", &args[0], f64::ceil)
+}
+
+fn round(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    to_int("round", &args[0], f64::round)
+}
+
+// floor, ceil and round give ints, ints are returned as they are
+fn to_int(name: &str, value: &Object, rounding: fn(f64) -> f64) -> Result<Object, Message> {
+    if let Object::IntValue(value) = value {
+        return Ok(Object::IntValue(*value));
+    }
+    Ok(Object::IntValue(whole(
+        name,
+        rounding(float(name, value)?),
+    )?))
+}
+
+fn min(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    extreme("min", args, |value, best| value < best)
+}
+
+fn max(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    extreme("max", args, |value, best| value > best)
+}
+
+// min and max take several numbers or a single list of them
+fn extreme(
+    name: &str,
+    args: Vec<Object>,
+    better: fn(&Object, &Object) -> bool,
+) -> Result<Object, Message> {
+    let values = match <[Object; 1]>::try_from(args) {
+        Ok([Object::List(values)]) => values.borrow().clone(),
+        Ok([value]) => vec![value],
+        Err(args) => args,
+    };
+    let mut best: Option<Object> = None;
+    for value in values {
+        float(name, &value)?;
+        if best.as_ref().is_none_or(|best| better(&value, best)) {
+            best = Some(value);
+        }
+    }
+    best.ok_or_else(|| Message::new(Code::EmptyList, &[&name]))
+}
+
+fn log(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let value = float("log", &args[0])?;
+    if value <= 0.0 {
+        return Err(undefined("log", &args[0]));
+    }
+    match args.get(1) {
+        Some(base) => match float("log", base)? {
+            // no power of 1 or of a base below 0 gives value
+            number if number <= 0.0 || number == 1.0 => Err(undefined("log", base)),
+            number => Ok(Object::FloatValue(value.log(number))),
+        },
+        None => Ok(Object::FloatValue(value.ln())),
+    }
+}
+
+// a float in [0, 1)
+fn random(interpreter: &mut Interpreter, _: Vec<Object>) -> Result<Object, Message> {
+    let bits = next_random(interpreter) >> 11;
+    Ok(Object::FloatValue(bits as f64 / (1u64 << 53) as f64))
+}
+
+// an int between low and high, both included
+fn randint(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let (low, high) = (int("randint", &args[0])?, int("randint", &args[1])?);
+    if low > high {
+        return Err(Message::new(
+            Code::UndefinedResult,
+            &[&"randint", &format!("{}, {}", low, high)],
+        ));
+    }
+    let span = high.abs_diff(low).wrapping_add(1);
+    let offset = match span {
+        0 => next_random(interpreter), // the whole range of i64
+        span => next_random(interpreter) % span,
+    };
+    Ok(Object::IntValue(low.wrapping_add(offset as i64)))
+}
+
+// makes random and randint repeat the same numbers on every run
+fn seed(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    interpreter.random_state = scramble(int("seed", &args[0])? as u64);
+    Ok(Object::Null)
+}
+
+pub fn default_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    scramble(nanos)
+}
+
+// splitmix64, spreads similar seeds apart and never gives the stuck state 0
+fn scramble(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)).max(1)
+}
+
+// xorshift64*
+fn next_random(interpreter: &mut Interpreter) -> u64 {
+    let mut x = interpreter.random_state;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    interpreter.random_state = x;
+    x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+}
+
+fn undefined(name: &str, value: &Object) -> Message {
+    Message::new(Code::UndefinedResult, &[&name, value])
+}
+
+fn overflow(name: &str) -> Message {
+    Message::new(Code::IntOverflow, &[&name])
+}
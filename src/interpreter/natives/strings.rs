@@ -0,0 +1,169 @@
+use super::{int, native, string, wrong_type, Native};
+use crate::{
+    interpreter::Interpreter,
+    messages::{Code, Message},
+    object::Object,
+    parser::expr::bool,
+};
+
+// strings are indexed by characters, not bytes, so "héllo"[1] is "é"
+pub const NATIVES: [Native; 13] = [
+    native("len", 1, 1, len),
+    native("substr", 2, 3, substr),
+    native("upper", 1, 1, |_, args| {
+        Ok(Object::StringValue(
+            string("upper", &args[0])?.to_uppercase().into(),
+        ))
+    }),
+    native("lower", 1, 1, |_, args| {
+        Ok(Object::StringValue(
+            string("lower", &args[0])?.to_lowercase().into(),
+        ))
+    }),
+    native("trim", 1, 1, |_, args| {
+        Ok(Object::StringValue(string("trim", &args[0])?.trim().into()))
+    }),
+    native("split", 1, 2, split),
+    native("join", 1, 2, join),
+    native("replace", 3, 3, replace),
+    native("contains", 2, 2, |_, args| {
+        let (text, part) = (string("contains", &args[0])?, string("contains", &args[1])?);
+        Ok(bool(text.contains(part)))
+    }),
+    native("starts_with", 2, 2, |_, args| {
+        let (text, prefix) = (
+            string("starts_with", &args[0])?,
+            string("starts_with", &args[1])?,
+        );
+        Ok(bool(text.starts_with(prefix)))
+    }),
+    native("index_of", 2, 2, index_of),
+    native("chars", 1, 1, chars),
+    native("repeat", 2, 2, repeat),
+];
+
+// a string, or a list, with len(), "abc".len() is the same as len("abc")
+fn len(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let len = match &args[0] {
+        Object::StringValue(text) => text.chars().count(),
+        Object::List(values) => values.borrow().len(),
+        value => return Err(wrong_type("len", "a string or a list", value)),
+    };
+    Ok(Object::IntValue(len as i64))
+}
+
+// substr(text, start) is the rest of text, substr(text, start, length) length characters of it
+fn substr(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let text = string("substr", &args[0])?;
+    let count = text.chars().count() as i64;
+    let start = int("substr", &args[1])?;
+    let end = match args.get(2) {
+        Some(length) => start.saturating_add(int("substr", length)?),
+        None => count,
+    };
+    for index in [start, end] {
+        if index < 0 || index > count {
+            return Err(Message::new(Code::StringIndexOutOfRange, &[&index, &count]));
+        }
+    }
+    if end < start {
+        return Err(Message::new(Code::UndefinedResult, &[&"substr", &args[2]]));
+    }
+    Ok(Object::StringValue(
+        text.chars()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect::<String>()
+            .into(),
+    ))
+}
+
+// splits on whitespace without a separator and into characters with an empty one
+fn split(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let text = string("split", &args[0])?;
+    let parts: Vec<Object> = match args.get(1) {
+        None => text
+            .split_whitespace()
+            .map(|part| Object::StringValue(part.into()))
+            .collect(),
+        Some(separator) => match string("split", separator)? {
+            "" => text
+                .chars()
+                .map(|c| Object::StringValue(c.to_string().into()))
+                .collect(),
+            separator => text
+                .split(separator)
+                .map(|part| Object::StringValue(part.into()))
+                .collect(),
+        },
+    };
+    Ok(interpreter.allocate(parts))
+}
+
+// joins the values of a list, whatever their type, as print would show them
+fn join(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let values = match &args[0] {
+        Object::List(values) => values,
+        value => return Err(wrong_type("join", "a list", value)),
+    };
+    let separator = match args.get(1) {
+        Some(separator) => string("join", separator)?,
+        None => "",
+    };
+    let values: Vec<String> = values.borrow().iter().map(Object::to_string).collect();
+    let separators = separator
+        .len()
+        .saturating_mul(values.len().saturating_sub(1));
+    interpreter.reserve(
+        values
+            .iter()
+            .map(String::len)
+            .fold(separators, usize::saturating_add),
+    )?;
+    Ok(Object::StringValue(values.join(separator).into()))
+}
+
+fn replace(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let text = string("replace", &args[0])?;
+    let (from, to) = (string("replace", &args[1])?, string("replace", &args[2])?);
+    // an empty pattern matches between every character
+    let matches = if from.is_empty() {
+        text.chars().count() + 1
+    } else {
+        text.matches(from).count()
+    };
+    let removed = matches * from.len();
+    interpreter.reserve((text.len() - removed).saturating_add(matches.saturating_mul(to.len())))?;
+    Ok(Object::StringValue(text.replace(from, to).into()))
+}
+
+// the character index of the first occurrence, -1 when there is none
+fn index_of(_: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let (text, part) = (string("index_of", &args[0])?, string("index_of", &args[1])?);
+    let index = match text.find(part) {
+        Some(offset) => text[..offset].chars().count() as i64,
+        None => -1,
+    };
+    Ok(Object::IntValue(index))
+}
+
+fn chars(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let text = string("chars", &args[0])?;
+    Ok(interpreter.allocate(
+        text.chars()
+            .map(|c| Object::StringValue(c.to_string().into()))
+            .collect(),
+    ))
+}
+
+fn repeat(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let text = string("repeat", &args[0])?;
+    let times = int("repeat", &args[1])?;
+    match usize::try_from(times) {
+        Ok(times) => {
+            interpreter.reserve(text.len().saturating_mul(times))?;
+            Ok(Object::StringValue(text.repeat(times).into()))
+        }
+        Err(_) => Err(Message::new(Code::UndefinedResult, &[&"repeat", &times])),
+    }
+}
@@ -0,0 +1,59 @@
+use super::{float, native, string, Native};
+use crate::{
+    interpreter::Interpreter,
+    messages::{Code, Message},
+    object::Object,
+};
+use std::{
+    env, process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const ENV: [Native; 1] = [native("env", 1, 1, |_, args| {
+    // a variable that is not set or not unicode is nil
+    match env::var(string("env", &args[0])?) {
+        Ok(value) => Ok(Object::StringValue(value.into())),
+        Err(_) => Ok(Object::Null),
+    }
+})];
+
+pub const TIME: [Native; 2] = [
+    native("clock", 0, 0, |_, _| {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |duration| duration.as_secs_f64());
+        Ok(Object::FloatValue(seconds))
+    }),
+    native("sleep", 1, 1, |interpreter, args| {
+        match Duration::try_from_secs_f64(float("sleep", &args[0])?) {
+            Ok(duration) => interpreter.sleep(duration)?,
+            Err(_) => return Err(Message::new(Code::UndefinedResult, &[&"sleep", &args[0]])),
+        }
+        Ok(Object::Null)
+    }),
+];
+
+// the garbage collector, it runs on its own as well
+pub const GC: [Native; 1] = [native("gc", 0, 0, |interpreter, _| {
+    Ok(Object::IntValue(interpreter.collect(&[]) as i64))
+})];
+
+pub const PROCESS: [Native; 2] = [
+    native("input", 0, 1, input),
+    native("pid", 0, 0, |_, _| {
+        Ok(Object::IntValue(process::id() as i64))
+    }),
+];
+
+// reads a line after printing the prompt, nil once the input has ended
+fn input(interpreter: &mut Interpreter, args: Vec<Object>) -> Result<Object, Message> {
+    let prompt = match args.first() {
+        Some(prompt) => string("input", prompt)?,
+        None => "",
+    };
+    match interpreter.io.read_line(prompt) {
+        Ok(Some(line)) => Ok(Object::StringValue(line.into())),
+        Ok(None) => Ok(Object::Null),
+        Err(err) => Err(Message::new(Code::IoFailed, &[&"input()", &err])),
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+// groups of natives that reach outside the interpreter
+#[derive(Clone, Copy, PartialEq)]
+pub enum Capability {
+    Fs,
+    Env,
+    Time,
+    Process,
+    // nothing uses the network yet, policies can already leave it out
+    Network,
+}
+
+pub const CAPABILITIES: [(&str, Capability); 5] = [
+    ("fs", Capability::Fs),
+    ("env", Capability::Env),
+    ("time", Capability::Time),
+    ("process", Capability::Process),
+    ("network", Capability::Network),
+];
+
+impl Capability {
+    pub fn find(name: &str) -> Option<Capability> {
+        CAPABILITIES
+            .iter()
+            .find(|(capability, _)| capability.eq_ignore_ascii_case(name))
+            .map(|(_, capability)| *capability)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, _) = CAPABILITIES
+            .iter()
+            .find(|(_, capability)| capability == self)
+            .unwrap_or_else(|| unreachable!("every capability has a name"));
+        write!(f, "{}", name)
+    }
+}
+
+// which capabilities scripts get, natives of the others are still defined but fail when called
+pub struct Sandbox {
+    pub fs: bool,
+    pub env: bool,
+    pub time: bool,
+    pub process: bool,
+    pub network: bool,
+}
+
+impl Default for Sandbox {
+    // everything is allowed outside of a sandbox
+    fn default() -> Self {
+        Sandbox {
+            fs: true,
+            env: true,
+            time: true,
+            process: true,
+            network: true,
+        }
+    }
+}
+
+impl Sandbox {
+    // nothing is allowed, for scripts that can't be trusted
+    pub fn locked() -> Self {
+        Sandbox {
+            fs: false,
+            env: false,
+            time: false,
+            process: false,
+            network: false,
+        }
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Fs => self.fs,
+            Capability::Env => self.env,
+            Capability::Time => self.time,
+            Capability::Process => self.process,
+            Capability::Network => self.network,
+        }
+    }
+
+    pub fn allow(&mut self, capability: Capability) {
+        match capability {
+            Capability::Fs => self.fs = true,
+            Capability::Env => self.env = true,
+            Capability::Time => self.time = true,
+            Capability::Process => self.process = true,
+            Capability::Network => self.network = true,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// rlox as a library so other programs can embed the scanner and interpreter
+pub mod diagnostic;
+pub mod error;
+pub mod format;
+pub mod interpreter;
+pub mod messages;
+pub mod optimizer;
+pub mod parser;
+pub mod presets;
+pub mod scanner;
+pub mod translate;
+pub use scanner::{object, token};
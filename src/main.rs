@@ -1,78 +1,266 @@
-mod interpreter;
-mod parser;
-mod scanner;
-use interpreter::Interpreter;
-use parser::Parser;
-
-use crate::scanner::*;
+mod cli;
+use cli::{Cli, Command, KeywordSource, Script};
+use rlox::{
+    diagnostic::SourceFile,
+    error::RloxError,
+    format,
+    interpreter::{io::StdIo, Interpreter},
+    object, optimizer,
+    parser::{ast::Ast, Parser},
+    presets,
+    scanner::{dialect::Dialect, Scanner},
+    translate,
+};
 use std::env;
 use std::fs;
 use std::io;
-use std::io::Write;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::rc::Rc;
 
-fn run(source: String) -> Vec<parser::stmt::Stmt> {
-    let mut scanner: Scanner = Scanner::new(source);
-    let tokens = scanner.scan_tokens();
+// scans and parses the source, the optimizer runs over the tree if asked to
+fn run(
+    source: Rc<SourceFile>,
+    dialect: &Rc<Dialect>,
+    nesting: usize,
+    optimize: bool,
+) -> Result<Ast, RloxError> {
+    let mut scanner: Scanner = Scanner::new(source, dialect.clone());
+    let tokens = match scanner.scan_tokens() {
+        Ok(tokens) => tokens,
+        Err(err) => return Err(err.localize(&dialect.messages)),
+    };
     let mut parser = Parser::new(tokens.to_vec());
-    parser.parse()
+    parser.set_max_nesting(nesting);
+    let mut ast = parser
+        .parse()
+        .map_err(|errors| RloxError::from(errors).localize(&dialect.messages))?;
+    if optimize {
+        optimizer::optimize(&mut ast);
+    }
+    Ok(ast)
 }
 
-fn run_file(path: &String) -> Result<(), String> {
-    let mut interpreter = Interpreter::new();
-    match fs::read_to_string(path) {
-        Err(msg) => Err(msg.to_string()),
-        Ok(source) => Ok(interpreter.interpret(run(source))),
+fn read_script(script: Script) -> Result<Rc<SourceFile>, RloxError> {
+    match script {
+        Script::File(path) => match fs::read_to_string(&path) {
+            Ok(source) => Ok(SourceFile::new(&path, source)),
+            Err(msg) => Err(RloxError::NoInput(format!("{} : {}", path, msg))),
+        },
+        Script::Stdin => {
+            let mut source = String::new();
+            match io::stdin().read_to_string(&mut source) {
+                Ok(_) => Ok(SourceFile::new("<stdin>", source)),
+                Err(msg) => Err(RloxError::NoInput(format!("<stdin> : {}", msg))),
+            }
+        }
+        Script::Inline(code) => Ok(SourceFile::new("<eval>", code)),
     }
 }
 
-fn run_prompt() -> () {
-    let mut interpreter = Interpreter::new();
+fn run_script(
+    source: Rc<SourceFile>,
+    args: Vec<String>,
+    dialect: &Rc<Dialect>,
+    interpreter: &mut Interpreter,
+    optimize: bool,
+) -> Result<(), RloxError> {
+    let ast = run(source, dialect, interpreter.limits().nesting, optimize)?;
+    let args = args
+        .into_iter()
+        .map(|arg| object::Object::StringValue(arg.into()))
+        .collect();
+    let args = interpreter.list(args);
+    interpreter.define_global("args", args);
+    interpreter
+        .interpret(&ast)
+        .map_err(|err| RloxError::from(err).localize(&dialect.messages))
+}
+
+// the dialect is loaded once and shared by the scanner of every line
+fn run_prompt(
+    dialect: &Rc<Dialect>,
+    interpreter: &mut Interpreter,
+    optimize: bool,
+) -> Result<(), RloxError> {
+    let args = interpreter.list(vec![]);
+    interpreter.define_global("args", args);
 
     loop {
         let mut line = String::new();
         print!(">>> ");
         let _ = io::stdout().flush(); //this is needed as rust stores print data to line buffer and > is not printed before the buffer is full
         match io::stdin().read_line(&mut line) {
+            Ok(0) => return Ok(()), // end of input (Ctrl+D / Ctrl+Z)
             Ok(_) => {
                 if line.trim() == "exit()" {
-                    return;
+                    return Ok(());
                 }
             }
             Err(msg) => {
                 println!("Error : {}", msg);
             }
         };
-        interpreter.interpret(run(line))
+        let source = SourceFile::new("<repl>", line);
+        let nesting = interpreter.limits().nesting;
+        let result = run(source, dialect, nesting, optimize).and_then(|ast| {
+            interpreter
+                .interpret(&ast)
+                .map_err(|err| RloxError::from(err).localize(&dialect.messages))
+        });
+        if let Err(err) = result {
+            println!("{}", err);
+        }
     }
 }
 
-fn _error(line: u32, message: String) -> () {
-    _report(line, "", message)
+// loads the dialect the scanner should use, --tokenfile and --preset win over a
+// .tokenfile next to the script, in the current directory and in the user config directory
+fn load_dialect(
+    keywords: Option<KeywordSource>,
+    script: Option<&Script>,
+) -> Result<Rc<Dialect>, RloxError> {
+    let dialect = match keywords {
+        Some(KeywordSource::Tokenfile(path)) => Dialect::from_file(&path)?,
+        Some(KeywordSource::Preset(name)) => match presets::find(&name) {
+            Some(preset) => Dialect::from_rules(preset.rules)?,
+            None => {
+                return Err(RloxError::Usage(format!(
+                    "Preset '{}' not found, run 'rlox presets list' to see the bundled presets.",
+                    name
+                )))
+            }
+        },
+        None => {
+            let mut locations = Vec::new();
+            if let Some(Script::File(path)) = script {
+                if let Some(directory) = Path::new(path).parent() {
+                    locations.push(directory.join(".tokenfile"));
+                }
+            }
+            locations.push(PathBuf::from(".tokenfile"));
+            if let Some(directory) = config_dir() {
+                locations.push(directory.join("rlox").join(".tokenfile"));
+            }
+            match locations.iter().find(|path| path.is_file()) {
+                Some(path) => Dialect::from_file(path)?,
+                None => Dialect::default(),
+            }
+        }
+    };
+    Ok(Rc::new(dialect))
 }
 
-fn _report(line: u32, location: &str, message: String) -> () {
-    println!("[line {}] Error {} : {}", line, location, message);
+// a dialect named on the command line, lox is plain lox
+fn named_dialect(name: &str) -> Result<Rc<Dialect>, RloxError> {
+    if name.eq_ignore_ascii_case("lox") {
+        return Ok(Rc::new(Dialect::default()));
+    }
+    let dialect = match presets::find(name) {
+        Some(preset) => Dialect::from_rules(preset.rules)?,
+        None => match fs::read_to_string(name) {
+            Ok(rules) => Dialect::from_rules(&rules)?,
+            Err(msg) => {
+                return Err(RloxError::NoInput(format!(
+                    "{} : {}, expect lox, a bundled preset or a .tokenfile",
+                    name, msg
+                )))
+            }
+        },
+    };
+    Ok(Rc::new(dialect))
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect(); //This contains location of the rlox.exe as first argument
-    if args.len() > 2 {
-        // more arguments are provided
-        println!("Usage: rlox [script]");
-        process::exit(64)
+fn config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
     }
-    if args.len() == 2 {
-        // file path is provided as first argument
-        match run_file(&args[1]) {
-            Ok(_) => process::exit(0),
-            Err(err) => {
-                println!("{}", err);
-                process::exit(1);
+}
+
+fn run_cli(cli: Cli) -> Result<(), RloxError> {
+    let sandbox = cli.sandbox.unwrap_or_default();
+    let mut interpreter = Interpreter::sandboxed(Box::new(StdIo), &sandbox);
+    interpreter.set_limits(cli.limits);
+    interpreter.set_gc_stress(cli.gc_stress);
+    match cli.command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Version => println!("rlox {}", env!("CARGO_PKG_VERSION")),
+        Command::PresetList => {
+            for preset in presets::PRESETS.iter() {
+                println!("{:<10} {}", preset.name, preset.description);
             }
         }
-    } else {
-        // no argument provided
-        run_prompt();
+        Command::PresetShow(name) => match presets::find(&name) {
+            Some(preset) => print!("{}", preset.rules),
+            None => {
+                return Err(RloxError::Usage(format!(
+                    "Preset '{}' not found, run 'rlox presets list' to see the bundled presets.",
+                    name
+                )))
+            }
+        },
+        Command::Repl => {
+            let result = run_prompt(
+                &load_dialect(cli.keywords, None)?,
+                &mut interpreter,
+                cli.optimize,
+            );
+            gc_stats(&interpreter, cli.gc_stats);
+            result?
+        }
+        Command::Run { script, args } => {
+            let dialect = load_dialect(cli.keywords, Some(&script))?;
+            let result = run_script(
+                read_script(script)?,
+                args,
+                &dialect,
+                &mut interpreter,
+                cli.optimize,
+            );
+            gc_stats(&interpreter, cli.gc_stats);
+            result?
+        }
+        Command::Translate { from, to, script } => {
+            let (from, to) = (named_dialect(&from)?, named_dialect(&to)?);
+            print!(
+                "{}",
+                translate::translate(read_script(script)?, &from, &to)?
+            );
+        }
+        Command::Fmt { script, check } => {
+            let dialect = load_dialect(cli.keywords, Some(&script))?;
+            let source = read_script(script)?;
+            let formatted = format::format(source.clone(), &dialect)
+                .map_err(|err| err.localize(&dialect.messages))?;
+            if !check {
+                print!("{}", formatted);
+            } else if formatted != source.text {
+                return Err(RloxError::Unformatted(source.name.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+// printed even when the script failed, that is when they matter most
+fn gc_stats(interpreter: &Interpreter, enabled: bool) {
+    if enabled {
+        eprintln!("{}", interpreter.gc_stats());
+    }
+}
+
+// the only place rlox exits from, see error.rs for the meaning of each exit code
+fn main() {
+    let result = Cli::parse(env::args().skip(1))
+        .map_err(|msg| RloxError::Usage(format!("{}\n\n{}", msg, cli::USAGE)))
+        .and_then(run_cli);
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(err.exit_code());
     }
 }
@@ -0,0 +1,441 @@
+use crate::{error::RloxError, scanner::tokenfile::config_error};
+use std::collections::HashMap;
+
+// every diagnostic rlox reports has a stable code so dialects can translate it
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Code {
+    UnexpectedCharacter,
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidNumber,
+    ExpectExpression,
+    ExpectVariableName,
+    SemicolonAfterVariable,
+    SemicolonAfterValue,
+    SemicolonAfterExpression,
+    ParenAfterWhile,
+    ParenAfterWhileCondition,
+    ParenAfterIf,
+    ParenAfterIfCondition,
+    BraceAfterBlock,
+    InvalidAssignment,
+    BracketAfterIndex,
+    ParenAfterExpression,
+    ParenAfterArguments,
+    MethodNameAfterDot,
+    NestingTooDeep,
+    UndefinedVariable,
+    IndexOutOfRange,
+    NotIndexable,
+    NotCallable,
+    WrongArity,
+    WrongArgumentType,
+    UndefinedResult,
+    IntOverflow,
+    EmptyList,
+    StringIndexOutOfRange,
+    NotAMethod,
+    MethodNotCalled,
+    NotANumber,
+    IoFailed,
+    FileFailed,
+    PermissionDenied,
+    StepLimit,
+    TimeLimit,
+    Interrupted,
+    MemoryLimit,
+    ArithmeticOverflow,
+    DifferentMeaning,
+    Untranslatable,
+}
+
+// code, english message and help, {0}, {1}... are replaced by the arguments of the error
+const MESSAGES: [(Code, &str, &str, Option<&str>); 43] = [
+    (
+        Code::UnexpectedCharacter,
+        "E0101",
+        "Unexpected character '{0}'.",
+        None,
+    ),
+    (
+        Code::UnterminatedString,
+        "E0102",
+        "Unterminated string.",
+        Some("add a closing '\"'"),
+    ),
+    (
+        Code::UnterminatedComment,
+        "E0103",
+        "Unterminated block comment.",
+        Some("add a closing '*/'"),
+    ),
+    (
+        Code::InvalidNumber,
+        "E0104",
+        "Invalid number literal '{0}'.",
+        None,
+    ),
+    (Code::ExpectExpression, "E0201", "Expect expression", None),
+    (
+        Code::ExpectVariableName,
+        "E0202",
+        "Expect variable name",
+        None,
+    ),
+    (
+        Code::SemicolonAfterVariable,
+        "E0203",
+        "Expect ';' after variable declaration",
+        Some("add ';' at the end of the statement"),
+    ),
+    (
+        Code::SemicolonAfterValue,
+        "E0204",
+        "Expect ';' after value.",
+        Some("add ';' at the end of the statement"),
+    ),
+    (
+        Code::SemicolonAfterExpression,
+        "E0205",
+        "Expect ';' after expression",
+        Some("add ';' at the end of the statement"),
+    ),
+    (
+        Code::ParenAfterWhile,
+        "E0206",
+        "Expect '(' after while .",
+        None,
+    ),
+    (
+        Code::ParenAfterWhileCondition,
+        "E0207",
+        "Expect ')' after condition",
+        None,
+    ),
+    (Code::ParenAfterIf, "E0208", "Expect '(' after 'if' .", None),
+    (
+        Code::ParenAfterIfCondition,
+        "E0209",
+        "Expect ')' after if condition.",
+        None,
+    ),
+    (
+        Code::BraceAfterBlock,
+        "E0210",
+        "Expect '}' after block.",
+        None,
+    ),
+    (
+        Code::InvalidAssignment,
+        "E0211",
+        "Invalid assignment target.",
+        Some("only variables can be assigned to"),
+    ),
+    (
+        Code::BracketAfterIndex,
+        "E0212",
+        "Expect ']' after index.",
+        None,
+    ),
+    (
+        Code::ParenAfterExpression,
+        "E0213",
+        "Expect ')' after expression ",
+        None,
+    ),
+    (
+        Code::ParenAfterArguments,
+        "E0214",
+        "Expect ')' after arguments.",
+        None,
+    ),
+    (
+        Code::MethodNameAfterDot,
+        "E0215",
+        "Expect method name after '.'.",
+        None,
+    ),
+    (
+        Code::NestingTooDeep,
+        "E0216",
+        "Too deeply nested.",
+        Some("split it up, at most {0} levels of nesting are allowed"),
+    ),
+    (
+        Code::UndefinedVariable,
+        "E0301",
+        "Undefined variable '{0}'.",
+        Some("declare it first with `var`"),
+    ),
+    (
+        Code::IndexOutOfRange,
+        "E0302",
+        "Index {0} out of range.",
+        Some("the list has {1} elements"),
+    ),
+    (
+        Code::NotIndexable,
+        "E0303",
+        "Only lists and strings can be indexed with an int.",
+        None,
+    ),
+    (Code::NotCallable, "E0304", "Can only call functions.", None),
+    (
+        Code::WrongArity,
+        "E0305",
+        "{0}() expects {1} arguments but got {2}.",
+        None,
+    ),
+    (
+        Code::WrongArgumentType,
+        "E0306",
+        "{0}() expects {1} but got {2}.",
+        None,
+    ),
+    (
+        Code::UndefinedResult,
+        "E0307",
+        "{0}() is not defined for {1}.",
+        None,
+    ),
+    (
+        Code::IntOverflow,
+        "E0308",
+        "The result of {0}() does not fit in an int.",
+        Some("use a float instead"),
+    ),
+    (Code::EmptyList, "E0309", "{0}() of an empty list.", None),
+    (
+        Code::StringIndexOutOfRange,
+        "E0310",
+        "Index {0} out of range.",
+        Some("the string has {1} characters"),
+    ),
+    (Code::NotAMethod, "E0311", "{1} has no method '{0}'.", None),
+    (
+        Code::MethodNotCalled,
+        "E0312",
+        "Method '{0}' must be called.",
+        Some("add '()' after it"),
+    ),
+    (
+        Code::NotANumber,
+        "E0313",
+        "Can't read '{0}' as a number.",
+        Some("numbers are written like 42, -3.5, 1_000, 1e9 or 0xFF"),
+    ),
+    (Code::IoFailed, "E0314", "{0} failed: {1}.", None),
+    (
+        Code::FileFailed,
+        "E0315",
+        "{0}() failed for '{1}': {2}.",
+        None,
+    ),
+    (
+        Code::PermissionDenied,
+        "E0316",
+        "permission denied: {0}",
+        Some("the sandbox doesn't allow {1}() to use {0}"),
+    ),
+    (
+        Code::StepLimit,
+        "E0317",
+        "execution budget exceeded",
+        Some("the script may only run {0} steps"),
+    ),
+    (
+        Code::TimeLimit,
+        "E0318",
+        "execution budget exceeded",
+        Some("the script may only run for {0}"),
+    ),
+    (Code::Interrupted, "E0320", "execution interrupted", None),
+    (
+        Code::MemoryLimit,
+        "E0321",
+        "memory budget exceeded",
+        Some("the script may only hold {0} bytes"),
+    ),
+    (
+        Code::ArithmeticOverflow,
+        "E0322",
+        "The result of this operation does not fit in an int.",
+        Some("use a float instead"),
+    ),
+    (
+        Code::DifferentMeaning,
+        "E0401",
+        "'{0}' has a different meaning in the target dialect.",
+        Some("rename it before translating"),
+    ),
+    (
+        Code::Untranslatable,
+        "E0402",
+        "The translation can't be scanned with the target dialect.",
+        None,
+    ),
+];
+
+impl Code {
+    pub fn id(self) -> &'static str {
+        self.english().1
+    }
+
+    fn english(self) -> &'static (Code, &'static str, &'static str, Option<&'static str>) {
+        MESSAGES
+            .iter()
+            .find(|(code, ..)| *code == self)
+            .unwrap_or_else(|| unreachable!("every code has an english message"))
+    }
+}
+
+// the text of a diagnostic, english until a dialect localizes it
+#[derive(Clone)]
+pub struct Message {
+    pub code: Code,
+    args: Vec<String>,
+    pub text: String,
+    pub help: Option<String>,
+}
+
+impl Message {
+    pub fn new(code: Code, args: &[&dyn ToString]) -> Self {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let (_, _, text, help) = code.english();
+        Message {
+            code,
+            text: fill(text, &args),
+            help: help.map(|help| fill(help, &args)),
+            args,
+        }
+    }
+
+    pub fn localize(&mut self, messages: &Messages) {
+        if let Some(text) = messages.texts.get(&self.code) {
+            self.text = fill(text, &self.args);
+        }
+        if let Some(help) = messages.helps.get(&self.code) {
+            self.help = Some(fill(help, &self.args));
+        }
+    }
+}
+
+// replaces each {n} in one pass, so braces inside the arguments are left alone
+fn fill(template: &str, args: &[String]) -> String {
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let arg = rest
+            .find('}')
+            .and_then(|close| Some((close, rest[1..close].parse::<usize>().ok()?)))
+            .and_then(|(close, index)| Some((close, args.get(index)?)));
+        match arg {
+            Some((close, arg)) => {
+                text.push_str(arg);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+// the [messages] section of a .tokenfile, codes left out keep their english message
+#[derive(Default)]
+pub struct Messages {
+    texts: HashMap<Code, String>,
+    helps: HashMap<Code, String>,
+}
+
+impl Messages {
+    // applies a `E0204 : message` or `E0204 help : help` rule
+    pub fn set(&mut self, number: usize, rule: &str) -> Result<(), RloxError> {
+        let (key, text) = match rule.split_once(':') {
+            Some((key, text)) => (key.trim(), text.trim().to_string()),
+            None => return Err(config_error(number, "expect 'E0000 : Your_Message'.")),
+        };
+        let (id, help) = match key.split_once(char::is_whitespace) {
+            Some((id, "help")) => (id, true),
+            Some(_) => {
+                return Err(config_error(
+                    number,
+                    &format!("expect 'E0000' or 'E0000 help' instead of '{}'.", key),
+                ))
+            }
+            None => (key, false),
+        };
+        let code = match MESSAGES.iter().find(|(_, code_id, ..)| *code_id == id) {
+            Some((code, ..)) => *code,
+            None => {
+                return Err(config_error(
+                    number,
+                    &format!("error code '{}' not found.", id),
+                ))
+            }
+        };
+        if help {
+            self.helps.insert(code, text);
+        } else {
+            self.texts.insert(code, text);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::dialect::Dialect;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        assert_eq!(fill("{0} of {1}, {0}", &args(&["a", "b"])), "a of b, a");
+        assert_eq!(fill("no args", &[]), "no args");
+    }
+
+    #[test]
+    fn arguments_are_not_filled_again() {
+        let text = fill("failed for '{0}': {1}", &args(&["/nope/{1}", "missing"]));
+        assert_eq!(text, "failed for '/nope/{1}': missing");
+    }
+
+    #[test]
+    fn unknown_placeholders_stay() {
+        assert_eq!(fill("{0} {3} {x} {", &args(&["a"])), "a {3} {x} {");
+    }
+
+    #[test]
+    fn dialects_replace_texts_and_helps() {
+        let rules = "[messages]\nE0302 : {0} is past the end\nE0302 help : only {1} values";
+        let dialect = Dialect::from_rules(rules).ok().unwrap();
+        let mut message = Message::new(Code::IndexOutOfRange, &[&7, &3]);
+        message.localize(&dialect.messages);
+        assert_eq!(message.text, "7 is past the end");
+        assert_eq!(message.help.as_deref(), Some("only 3 values"));
+        // codes the dialect leaves out stay in english
+        let mut message = Message::new(Code::UndefinedVariable, &[&"x"]);
+        message.localize(&dialect.messages);
+        assert_eq!(message.text, "Undefined variable 'x'.");
+    }
+
+    #[test]
+    fn unknown_codes_are_rejected() {
+        let error = Dialect::from_rules("[messages]\n\nE9999 : never")
+            .err()
+            .unwrap();
+        assert!(error.exit_code() == 78);
+        assert!(error
+            .to_string()
+            .ends_with("line 3 : error code 'E9999' not found."));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    object::Object,
+    parser::{
+        ast::{Ast, ExprId, SideTable, StmtId},
+        expr::{apply_binary, apply_unary, is_truthy, Expr},
+        stmt::Stmt,
+    },
+    token::Tokentype,
+};
+
+// the value of each expression that was folded, by the id of the expression
+pub type Constants = SideTable<ExprId, Object>;
+
+// works out before the program runs what doesn't depend on it running: operators on literals
+// are folded and branches that can't be taken are dropped. the program prints the same either way
+pub fn optimize(ast: &mut Ast) -> Constants {
+    let mut constants = Constants::default();
+    for statement in ast.statements.clone() {
+        optimize_statement(ast, &mut constants, statement);
+    }
+    constants
+}
+
+fn optimize_statement(ast: &mut Ast, constants: &mut Constants, id: StmtId) {
+    match ast.stmt(id).clone() {
+        Stmt::Expression { expression } | Stmt::Print { expression, .. } => {
+            optimize_expression(ast, constants, expression)
+        }
+        Stmt::Var { initalizer, .. } => optimize_expression(ast, constants, initalizer),
+        Stmt::Block { statements } => {
+            for statement in statements {
+                optimize_statement(ast, constants, statement);
+            }
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            optimize_expression(ast, constants, condition);
+            optimize_statement(ast, constants, then_branch);
+            if let Some(else_branch) = else_branch {
+                optimize_statement(ast, constants, else_branch);
+            }
+            if let Some(value) = literal(ast, condition) {
+                // like the interpreter only true takes the then branch
+                let branch = if value == Object::True {
+                    Some(then_branch)
+                } else {
+                    else_branch
+                };
+                let statement = match branch {
+                    Some(branch) => ast.stmt(branch).clone(),
+                    None => empty(),
+                };
+                ast.replace_stmt(id, statement);
+            }
+        }
+        Stmt::While {
+            condition, body, ..
+        } => {
+            optimize_expression(ast, constants, condition);
+            optimize_statement(ast, constants, body);
+            if literal(ast, condition).is_some_and(|value| value != Object::True) {
+                ast.replace_stmt(id, empty());
+            }
+        }
+    }
+}
+
+fn optimize_expression(ast: &mut Ast, constants: &mut Constants, id: ExprId) {
+    let folded = match ast.expr(id).clone() {
+        Expr::Literal { .. } | Expr::Variable { .. } => None,
+        Expr::Grouping { expression } => {
+            optimize_expression(ast, constants, expression);
+            literal(ast, expression)
+        }
+        Expr::Unary { operator, right } => {
+            optimize_expression(ast, constants, right);
+            literal(ast, right).and_then(|right| fold_unary(operator.tokentype, right))
+        }
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => {
+            optimize_expression(ast, constants, left);
+            optimize_expression(ast, constants, right);
+            match (literal(ast, left), literal(ast, right)) {
+                (Some(left), Some(right)) => fold_binary(operator.tokentype, left, right),
+                _ => None,
+            }
+        }
+        Expr::Logical {
+            left,
+            operator,
+            right,
+        } => {
+            optimize_expression(ast, constants, left);
+            optimize_expression(ast, constants, right);
+            // or stops at a truthy value, and at a falsy one, otherwise it is the right side
+            match literal(ast, left) {
+                Some(value) => {
+                    let truthy = is_truthy(&value) == Object::True;
+                    if truthy == (operator.tokentype == Tokentype::Or) {
+                        Some(value)
+                    } else {
+                        let right = ast.expr(right).clone();
+                        ast.replace_expr(id, right);
+                        literal(ast, id)
+                    }
+                }
+                None => None,
+            }
+        }
+        Expr::Assign { value, .. } => {
+            optimize_expression(ast, constants, value);
+            None
+        }
+        // indexing a literal can fail, the error is left for when the program runs
+        Expr::Index { object, index, .. } => {
+            optimize_expression(ast, constants, object);
+            optimize_expression(ast, constants, index);
+            None
+        }
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            optimize_expression(ast, constants, callee);
+            for argument in arguments {
+                optimize_expression(ast, constants, argument);
+            }
+            None
+        }
+        Expr::Get { object, .. } => {
+            optimize_expression(ast, constants, object);
+            None
+        }
+    };
+    if let Some(value) = folded {
+        constants.insert(id, value.clone());
+        ast.replace_expr(id, Expr::Literal { value });
+    }
+}
+
+// an overflow is not folded, the program reports it when it runs
+fn fold_unary(operator: Tokentype, right: Object) -> Option<Object> {
+    apply_unary(operator, right).ok()
+}
+
+// joined strings are left too, the memory limit is checked when they are made
+fn fold_binary(operator: Tokentype, left: Object, right: Object) -> Option<Object> {
+    if let (Object::StringValue(_), Object::StringValue(_)) = (&left, &right) {
+        if operator == Tokentype::Plus {
+            return None;
+        }
+    }
+    apply_binary(operator, left, right).ok()
+}
+
+fn literal(ast: &Ast, id: ExprId) -> Option<Object> {
+    match ast.expr(id) {
+        Expr::Literal { value } => Some(value.clone()),
+        _ => None,
+    }
+}
+
+// what a removed statement turns into
+fn empty() -> Stmt {
+    Stmt::Block {
+        statements: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    fn optimized(source: &str) -> (Ast, Constants) {
+        let mut ast = parse_source(source);
+        let constants = optimize(&mut ast);
+        (ast, constants)
+    }
+
+    // the expression printed by the statement at index
+    fn printed(ast: &Ast, index: usize) -> ExprId {
+        match ast.stmt(ast.statements[index]) {
+            Stmt::Print { expression, .. } => *expression,
+            _ => unreachable!("only print statements are tested"),
+        }
+    }
+
+    #[test]
+    fn folded_expressions_are_constants() {
+        let (ast, constants) = optimized("print 60 * 60 * 24; print -(2.5); print 0 or 3;");
+        let values: Vec<Option<&Object>> = (0..3)
+            .map(|index| constants.get(printed(&ast, index)))
+            .collect();
+        let expected = [
+            Object::IntValue(86400),
+            Object::FloatValue(-2.5),
+            Object::IntValue(3),
+        ];
+        assert!(values == expected.iter().map(Some).collect::<Vec<_>>());
+        assert!(
+            ast.expr(printed(&ast, 0))
+                == &Expr::Literal {
+                    value: Object::IntValue(86400)
+                }
+        );
+    }
+
+    #[test]
+    fn what_depends_on_the_program_is_left() {
+        let (ast, constants) = optimized(
+            "var x = 1; print x + 1 * 2; print 9223372036854775807 + 1; print true and x;",
+        );
+        let sum = printed(&ast, 1);
+        assert!(constants.get(sum).is_none());
+        assert_eq!(ast.display(sum).to_string(), "(+ (variable : x) 2)");
+        // an overflow is reported when the program runs
+        assert!(constants.get(printed(&ast, 2)).is_none());
+        assert_eq!(ast.display(printed(&ast, 3)).to_string(), "(variable : x)");
+    }
+}
@@ -1,89 +1,138 @@
-use core::panic;
+pub mod ast;
 pub mod expr;
 pub mod stmt;
-use crate::scanner::{
-    object::Object,
-    token::{Token, Tokentype},
+use crate::{
+    diagnostic::Diagnostic,
+    messages::{Code, Message, Messages},
+    scanner::{
+        object::Object,
+        symbol::Symbol,
+        token::{Token, Tokentype},
+    },
 };
+use ast::{Ast, ExprId, Span, StmtId};
 use expr::Expr;
+use std::{fmt, rc::Rc};
 use stmt::Stmt;
 
+pub struct ParseError {
+    pub token: Box<Token>, // boxed to keep Result<Expr, ParseError> small
+    pub message: Message,
+}
+
+impl ParseError {
+    pub fn localize(&mut self, messages: &Messages) {
+        self.message.localize(messages)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Diagnostic::at_token(&self.token, &self.message))
+    }
+}
+
 pub struct Parser {
     current: usize,
     tokens: Vec<Token>,
-    statements: Vec<Stmt>,
+    ast: Ast,
+    errors: Vec<ParseError>,
+    // how deep the statement or expression being parsed is nested
+    depth: usize,
+    max_nesting: usize,
 }
 
+// deeper nesting would overflow the stack of the parser or the interpreter of a debug build,
+// left-associative chains like 1 + 2 + 3 nest too
+pub const MAX_NESTING: usize = 128;
+
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
         Parser {
             tokens,
             current: 0,
-            statements: Vec::new(),
+            ast: Ast::default(),
+            errors: Vec::new(),
+            depth: 0,
+            max_nesting: MAX_NESTING,
         }
     }
+
+    // how deep statements and expressions may nest, see Limits::nesting
+    pub fn set_max_nesting(&mut self, nesting: usize) {
+        self.max_nesting = nesting
+    }
 }
 
 // this impl implements grammar written in Expr.rs
 impl Parser {
-    pub fn parse(&mut self) -> Vec<Stmt> {
+    pub fn parse(&mut self) -> Result<Ast, Vec<ParseError>> {
         while !self.is_at_end() {
-            let statement = self.declaration();
-            match statement {
-                Ok(val) => self.statements.push(val),
-                Err(msg) => {
-                    println!("{}", msg);
+            self.depth = 0;
+            match self.declaration() {
+                Ok(statement) => self.ast.statements.push(statement),
+                Err(error) => {
+                    self.errors.push(error);
                     self.synchronize();
                 }
             }
         }
-        self.statements.clone()
+        if self.errors.is_empty() {
+            Ok(std::mem::take(&mut self.ast))
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
     }
 
-    fn declaration(&mut self) -> Result<Stmt, &str> {
+    fn declaration(&mut self) -> Result<StmtId, ParseError> {
         if self.match_tokens(&[Tokentype::Var]) {
-            self.var_declaration()
+            let doc = self.previous().doc.clone();
+            self.var_declaration(doc)
         } else {
-            Ok(self.statement())
+            self.statement()
         }
     }
 
-    fn var_declaration(&mut self) -> Result<Stmt, &str> {
-        let name = self.consume(Tokentype::Identifier, "Expect variable name");
+    fn var_declaration(&mut self, doc: Option<Rc<str>>) -> Result<StmtId, ParseError> {
+        let start = self.previous().offset;
+        let name = self
+            .consume(Tokentype::Identifier, Code::ExpectVariableName)?
+            .clone();
 
-        match name {
-            Ok(val) => {
-                let name = val;
-                let initalizer: Expr;
-                if self.match_tokens(&[Tokentype::Equal]) {
-                    initalizer = self.expression();
-                } else {
-                    initalizer = Expr::Literal {
-                        value: Object::Null,
-                    };
-                }
-                let _ = self.consume(
-                    Tokentype::Semicolon,
-                    "Expect ';' after variable declaration",
-                );
-                Ok(Stmt::Var { name, initalizer })
-            }
-            Err(msg) => {
-                println!("{}", msg);
-                Err("Parsing error occured")
-            }
-        }
+        let initalizer = if self.match_tokens(&[Tokentype::Equal]) {
+            self.expression()?
+        } else {
+            // an empty span right after the name
+            let end = self.end_of_previous();
+            let value = Object::Null;
+            self.ast
+                .add_expr(Expr::Literal { value }, Span { start: end, end })
+        };
+        self.consume_semicolon(Code::SemicolonAfterVariable)?;
+        let statement = Stmt::Var {
+            name,
+            initalizer,
+            doc,
+        };
+        Ok(self.stmt(start, statement))
+    }
+
+    fn statement(&mut self) -> Result<StmtId, ParseError> {
+        self.nest()?;
+        let statement = self.nested_statement();
+        self.depth -= 1;
+        statement
     }
 
-    fn statement(&mut self) -> Stmt {
+    fn nested_statement(&mut self) -> Result<StmtId, ParseError> {
         if self.match_tokens(&[Tokentype::Print]) {
             self.print_statement()
         } else if self.match_tokens(&[Tokentype::If]) {
             self.if_statement()
         } else if self.match_tokens(&[Tokentype::LeftBrace]) {
-            Stmt::Block {
-                statements: self.block(),
-            }
+            let start = self.previous().offset;
+            let statements = self.block()?;
+            Ok(self.stmt(start, Stmt::Block { statements }))
         } else if self.match_tokens(&[Tokentype::While]) {
             self.while_statement()
         } else {
@@ -91,242 +140,338 @@ impl Parser {
         }
     }
 
-    fn while_statement(&mut self) -> Stmt {
-        let _ = self.consume(Tokentype::LeftParen, "Expect '(' after while .");
-        let condition = self.expression();
-        let _ = self.consume(Tokentype::RightParen, "Expect ')' after condition");
+    fn while_statement(&mut self) -> Result<StmtId, ParseError> {
+        let keyword = self.previous().clone();
+        self.consume(Tokentype::LeftParen, Code::ParenAfterWhile)?;
+        let condition = self.expression()?;
+        self.consume(Tokentype::RightParen, Code::ParenAfterWhileCondition)?;
 
-        let body = self.statement();
+        let body = self.statement()?;
 
-        Stmt::While {
+        let start = keyword.offset;
+        let statement = Stmt::While {
+            keyword,
             condition,
-            body: Box::new(body),
-        }
+            body,
+        };
+        Ok(self.stmt(start, statement))
     }
 
-    fn if_statement(&mut self) -> Stmt {
-        let _ = self.consume(Tokentype::LeftParen, "Expect '(' after 'if' .");
-        let condition = self.expression();
-        let _ = self.consume(Tokentype::RightParen, "Expect ')' after if condition.");
+    fn if_statement(&mut self) -> Result<StmtId, ParseError> {
+        let start = self.previous().offset;
+        self.consume(Tokentype::LeftParen, Code::ParenAfterIf)?;
+        let condition = self.expression()?;
+        self.consume(Tokentype::RightParen, Code::ParenAfterIfCondition)?;
 
-        let then_brach = self.statement();
+        let then_branch = self.statement()?;
         let mut else_branch = Option::None;
         if self.match_tokens(&[Tokentype::Else]) {
-            else_branch = Some(self.statement());
+            else_branch = Some(self.statement()?);
         }
 
-        Stmt::If {
+        let statement = Stmt::If {
             condition,
-            then_branch: Box::new(then_brach),
-            else_branch: Box::new(else_branch),
-        }
+            then_branch,
+            else_branch,
+        };
+        Ok(self.stmt(start, statement))
     }
 
-    fn block(&mut self) -> Vec<Stmt> {
-        let mut statements: Vec<Stmt> = Vec::new();
+    fn block(&mut self) -> Result<Vec<StmtId>, ParseError> {
+        let mut statements: Vec<StmtId> = Vec::new();
 
         while !self.check(Tokentype::RightBrace) && !self.is_at_end() {
-            match self.declaration() {
-                Ok(val) => statements.push(val),
-                Err(msg) => println!("{}", msg),
-            }
+            statements.push(self.declaration()?);
         }
 
-        let _ = self.consume(Tokentype::RightBrace, "Error : Expect '}' after block.");
-        statements
+        self.consume(Tokentype::RightBrace, Code::BraceAfterBlock)?;
+        Ok(statements)
     }
 
-    fn print_statement(&mut self) -> Stmt {
-        let value = self.expression();
-        let _ = self.consume(Tokentype::Semicolon, "Expect ';' after value.");
-        Stmt::Print { expression: value }
+    fn print_statement(&mut self) -> Result<StmtId, ParseError> {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+        self.consume_semicolon(Code::SemicolonAfterValue)?;
+        let start = keyword.offset;
+        let statement = Stmt::Print {
+            keyword,
+            expression: value,
+        };
+        Ok(self.stmt(start, statement))
     }
 
-    fn expression_statement(&mut self) -> Stmt {
-        let expr = self.expression();
-        let _ = self.consume(Tokentype::Semicolon, "Expect ';' after expression");
-        Stmt::Expression { expression: expr }
+    fn expression_statement(&mut self) -> Result<StmtId, ParseError> {
+        let expr = self.expression()?;
+        self.consume_semicolon(Code::SemicolonAfterExpression)?;
+        let start = self.ast.expr_span(expr).start;
+        Ok(self.stmt(start, Stmt::Expression { expression: expr }))
     }
 
-    fn expression(&mut self) -> Expr {
-        match self.assignment() {
-            Ok(val) => val,
-            Err(msg) => {
-                println!("{}", msg);
-                Expr::Literal {
-                    value: Object::Null,
-                }
-            }
+    fn expression(&mut self) -> Result<ExprId, ParseError> {
+        self.nest()?;
+        let expr = self.assignment();
+        self.depth -= 1;
+        expr
+    }
+
+    // counts a level of nesting, errors the parse fails with reset the depth
+    fn nest(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > self.max_nesting {
+            return Err(ParseError {
+                token: Box::new(self.peek().clone()),
+                message: Message::new(Code::NestingTooDeep, &[&self.max_nesting]),
+            });
         }
+        Ok(())
     }
 
-    fn assignment(&mut self) -> Result<Expr, &str> {
-        let expr = self.or();
+    fn assignment(&mut self) -> Result<ExprId, ParseError> {
+        let expr = self.or()?;
 
         if self.match_tokens(&[Tokentype::Equal]) {
-            let _equals = self.previous();
-            let value = self.assignment()?;
-
-            match expr {
-                Expr::Variable { name } => Ok(Expr::Assign {
-                    name,
-                    value: Box::new(value),
-                }),
-                _ => Err("Error : Invalid assignment target."),
+            let equals = self.previous().clone();
+            let value = self.expression()?;
+
+            match self.ast.expr(expr) {
+                Expr::Variable { name } => {
+                    let name = name.clone();
+                    let start = self.ast.expr_span(expr).start;
+                    Ok(self.expr(start, Expr::Assign { name, value }))
+                }
+                _ => Err(error(equals, Code::InvalidAssignment)),
             }
         } else {
             Ok(expr)
         }
     }
 
-    fn or(&mut self) -> Expr {
-        let mut expr = self.and();
+    fn or(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.and()?;
 
+        let depth = self.depth;
         while self.match_tokens(&[Tokentype::Or]) {
-            let operator = self.previous();
-            let right = self.and();
-            expr = Expr::Logical {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            }
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.and()?;
+            expr = self.binary(expr, operator, right);
         }
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn and(&mut self) -> Expr {
-        let mut expr = self.equality();
+    fn and(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.equality()?;
 
+        let depth = self.depth;
         while self.match_tokens(&[Tokentype::And]) {
-            let operator = self.previous();
-            let right = self.equality();
-            expr = Expr::Logical {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            }
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.equality()?;
+            expr = self.binary(expr, operator, right);
         }
-
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn equality(&mut self) -> Expr {
-        let mut expr: Expr = self.comparison();
+    fn equality(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.comparison()?;
 
+        let depth = self.depth;
         while self.match_tokens(&[Tokentype::BangEqual, Tokentype::EqualEqual]) {
-            let operator = self.previous();
-            let right = self.comparison();
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            };
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.comparison()?;
+            expr = self.binary(expr, operator, right);
         }
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn comparison(&mut self) -> Expr {
-        let mut expr = self.term();
+    fn comparison(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.term()?;
+        let depth = self.depth;
         while self.match_tokens(&[
             Tokentype::Greater,
             Tokentype::GreaterEqual,
             Tokentype::Less,
             Tokentype::LessEqual,
         ]) {
-            let operator = self.previous();
-            let right = self.term();
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            }
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.term()?;
+            expr = self.binary(expr, operator, right);
         }
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn term(&mut self) -> Expr {
-        let mut expr = self.factor();
+    fn term(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.factor()?;
 
+        let depth = self.depth;
         while self.match_tokens(&[Tokentype::Minus, Tokentype::Plus]) {
-            let operator = self.previous();
-            let right = self.factor();
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            }
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.factor()?;
+            expr = self.binary(expr, operator, right);
         }
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn factor(&mut self) -> Expr {
-        let mut expr: Expr = self.unary();
+    fn factor(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.unary()?;
 
+        let depth = self.depth;
         while self.match_tokens(&[Tokentype::Slash, Tokentype::Star]) {
-            let operator = self.previous();
-            let right = self.unary();
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                operator,
-                right: Box::new(right),
-            }
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.unary()?;
+            expr = self.binary(expr, operator, right);
         }
-        expr
+        self.depth = depth;
+        Ok(expr)
     }
 
-    fn unary(&mut self) -> Expr {
+    fn unary(&mut self) -> Result<ExprId, ParseError> {
         if self.match_tokens(&[Tokentype::Bang, Tokentype::Minus]) {
-            let operator = self.previous();
-            let right = self.unary();
-            return Expr::Unary {
-                operator,
-                right: Box::new(right),
-            };
+            let operator = self.previous().clone();
+            self.nest()?;
+            let right = self.unary()?;
+            self.depth -= 1;
+            let start = operator.offset;
+            return Ok(self.expr(start, Expr::Unary { operator, right }));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<ExprId, ParseError> {
+        let mut expr = self.primary()?;
+        let start = self.ast.expr_span(expr).start;
+
+        let depth = self.depth;
+        loop {
+            if self.check(Tokentype::LeftBracket)
+                || self.check(Tokentype::LeftParen)
+                || self.check(Tokentype::Dot)
+            {
+                self.nest()?;
+            }
+            if self.match_tokens(&[Tokentype::LeftBracket]) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(Tokentype::RightBracket, Code::BracketAfterIndex)?;
+                let index = Expr::Index {
+                    object: expr,
+                    bracket,
+                    index,
+                };
+                expr = self.expr(start, index);
+            } else if self.match_tokens(&[Tokentype::LeftParen]) {
+                expr = self.finish_call(start, expr)?;
+            } else if self.match_tokens(&[Tokentype::Dot]) {
+                let name = self
+                    .consume(Tokentype::Identifier, Code::MethodNameAfterDot)?
+                    .clone();
+                expr = self.expr(start, Expr::Get { object: expr, name });
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
         }
-        self.primary()
     }
 
-    fn primary(&mut self) -> Expr {
+    fn finish_call(&mut self, start: usize, callee: ExprId) -> Result<ExprId, ParseError> {
+        let mut arguments = Vec::new();
+        if !self.check(Tokentype::RightParen) {
+            loop {
+                arguments.push(self.expression()?);
+                if !self.match_tokens(&[Tokentype::Comma]) {
+                    break;
+                }
+            }
+        }
+        let paren = self
+            .consume(Tokentype::RightParen, Code::ParenAfterArguments)?
+            .clone();
+        let call = Expr::Call {
+            callee,
+            paren,
+            arguments,
+        };
+        Ok(self.expr(start, call))
+    }
+
+    fn primary(&mut self) -> Result<ExprId, ParseError> {
+        let start = self.peek().offset;
         if self.match_tokens(&[Tokentype::False]) {
-            return Expr::Literal {
-                value: Object::False,
-            };
+            let value = Object::False;
+            return Ok(self.expr(start, Expr::Literal { value }));
         };
         if self.match_tokens(&[Tokentype::True]) {
-            return Expr::Literal {
-                value: Object::True,
-            };
+            let value = Object::True;
+            return Ok(self.expr(start, Expr::Literal { value }));
         }
 
         if self.match_tokens(&[Tokentype::Nil]) {
-            return Expr::Literal {
-                value: Object::Null,
-            };
+            let value = Object::Null;
+            return Ok(self.expr(start, Expr::Literal { value }));
         }
 
         if self.match_tokens(&[Tokentype::Number, Tokentype::String]) {
-            return Expr::Literal {
-                value: self.previous().literal,
-            };
+            let value = self.previous().literal.clone();
+            return Ok(self.expr(start, Expr::Literal { value }));
         }
         if self.match_tokens(&[Tokentype::Identifier]) {
-            return Expr::Variable {
-                name: self.previous(),
-            };
+            let name = self.previous().clone();
+            return Ok(self.expr(start, Expr::Variable { name }));
         }
         if self.match_tokens(&[Tokentype::LeftParen]) {
-            let expr = self.expression();
-            let _ = self.consume(Tokentype::RightParen, "Expect ')' after expression ");
-            Expr::Grouping {
-                expression: Box::new(expr),
-            }
+            let expression = self.expression()?;
+            self.consume(Tokentype::RightParen, Code::ParenAfterExpression)?;
+            Ok(self.expr(start, Expr::Grouping { expression }))
         } else {
-            error(self.peek(), "Expect expression");
-            panic!("yo wtf");
+            Err(error(self.peek().clone(), Code::ExpectExpression))
         }
     }
 }
 
+// nodes go into the arena spanning from start to the end of the last token parsed
+impl Parser {
+    fn expr(&mut self, start: usize, expr: Expr) -> ExprId {
+        let end = self.end_of_previous();
+        self.ast.add_expr(expr, Span { start, end })
+    }
+
+    fn stmt(&mut self, start: usize, stmt: Stmt) -> StmtId {
+        let end = self.end_of_previous();
+        self.ast.add_stmt(stmt, Span { start, end })
+    }
+
+    fn binary(&mut self, left: ExprId, operator: Token, right: ExprId) -> ExprId {
+        let start = self.ast.expr_span(left).start;
+        let expr = match operator.tokentype {
+            Tokentype::And | Tokentype::Or => Expr::Logical {
+                left,
+                operator,
+                right,
+            },
+            _ => Expr::Binary {
+                left,
+                operator,
+                right,
+            },
+        };
+        self.expr(start, expr)
+    }
+
+    fn end_of_previous(&self) -> usize {
+        let previous = self.previous();
+        previous.offset + previous.lexeme.len()
+    }
+}
+
 impl Parser {
     // checks if the current token matches any of the token types provided
     fn match_tokens(&mut self, types: &[Tokentype]) -> bool {
@@ -346,33 +491,46 @@ impl Parser {
         self.peek().tokentype == token
     }
 
-    fn advance(&mut self) -> Token {
+    fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
         }
-        let token = self.previous();
-        token
+        self.previous()
     }
 
     fn is_at_end(&self) -> bool {
         self.peek().tokentype == Tokentype::Eof
     }
 
-    fn peek(&self) -> Token {
-        self.tokens[self.current].clone()
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current]
     }
 
-    fn previous(&self) -> Token {
-        self.tokens[self.current - 1].clone()
+    fn previous(&self) -> &Token {
+        &self.tokens[self.current - 1]
     }
 
-    fn consume(&mut self, ty: Tokentype, message: &str) -> Result<Token, &str> {
+    fn consume(&mut self, ty: Tokentype, code: Code) -> Result<&Token, ParseError> {
         if self.check(ty) {
             Ok(self.advance())
         } else {
-            error(self.peek(), message);
-            Err("some error occured check logs")
+            Err(error(self.peek().clone(), code))
+        }
+    }
+
+    // a missing ';' is reported right after the previous token rather than at the next one
+    fn consume_semicolon(&mut self, code: Code) -> Result<(), ParseError> {
+        if self.check(Tokentype::Semicolon) {
+            self.advance();
+            return Ok(());
         }
+        let previous = self.previous();
+        let mut token = self.peek().clone();
+        token.lexeme = Symbol::new("");
+        token.offset = previous.offset + previous.lexeme.len();
+        token.line = previous.line;
+        token.column = previous.column + previous.lexeme.chars().count();
+        Err(error(token, code))
     }
 
     fn synchronize(&mut self) {
@@ -398,10 +556,25 @@ impl Parser {
     }
 }
 
-fn error(token: Token, message: &str) {
-    if token.tokentype == Tokentype::Eof {
-        println!("{} at end {}", token.line, message)
-    } else {
-        println!("{} at {} ' {}", token.line, token.lexeme, message)
+fn error(token: Token, code: Code) -> ParseError {
+    ParseError {
+        token: Box::new(token),
+        message: Message::new(code, &[]),
     }
 }
+
+// the ast of source written in plain lox, for tests of what is built from it
+#[cfg(test)]
+pub(crate) fn parse_source(source: &str) -> Ast {
+    use crate::{
+        diagnostic::SourceFile,
+        scanner::{dialect::Dialect, Scanner},
+    };
+    let source = SourceFile::new("test.lox", source.to_string());
+    let tokens = Scanner::new(source, Rc::new(Dialect::default()))
+        .scan_tokens()
+        .ok()
+        .unwrap()
+        .to_vec();
+    Parser::new(tokens).parse().ok().unwrap()
+}
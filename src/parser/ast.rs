@@ -0,0 +1,224 @@
+use super::{expr::Expr, stmt::Stmt};
+use std::{fmt, marker::PhantomData};
+
+// handles of nodes in an Ast, they stay valid for as long as the tree does so tooling can refer
+// to a node without holding on to it
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExprId(u32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StmtId(u32);
+
+pub trait NodeId: Copy {
+    fn index(self) -> usize;
+}
+
+impl NodeId for ExprId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl NodeId for StmtId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+// byte offsets of the source a node was parsed from, end is exclusive
+#[derive(Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+// every node of a program, children are ids into the same tree
+#[derive(Default)]
+pub struct Ast {
+    exprs: Vec<(Expr, Span)>,
+    stmts: Vec<(Stmt, Span)>,
+    // the top level statements in order
+    pub statements: Vec<StmtId>,
+}
+
+impl Ast {
+    pub fn add_expr(&mut self, expr: Expr, span: Span) -> ExprId {
+        self.exprs.push((expr, span));
+        ExprId(self.exprs.len() as u32 - 1)
+    }
+
+    pub fn add_stmt(&mut self, stmt: Stmt, span: Span) -> StmtId {
+        self.stmts.push((stmt, span));
+        StmtId(self.stmts.len() as u32 - 1)
+    }
+
+    // swaps the node behind id, it keeps its span and everything pointing at it sees the new one
+    pub fn replace_expr(&mut self, id: ExprId, expr: Expr) {
+        self.exprs[id.index()].0 = expr;
+    }
+
+    pub fn replace_stmt(&mut self, id: StmtId, stmt: Stmt) {
+        self.stmts[id.index()].0 = stmt;
+    }
+
+    pub fn expr(&self, id: ExprId) -> &Expr {
+        &self.exprs[id.index()].0
+    }
+
+    pub fn stmt(&self, id: StmtId) -> &Stmt {
+        &self.stmts[id.index()].0
+    }
+
+    pub fn expr_span(&self, id: ExprId) -> Span {
+        self.exprs[id.index()].1
+    }
+
+    pub fn stmt_span(&self, id: StmtId) -> Span {
+        self.stmts[id.index()].1
+    }
+
+    // prints an expression as a lisp like tree, for debugging
+    pub fn display(&self, id: ExprId) -> ExprDisplay<'_> {
+        ExprDisplay { ast: self, id }
+    }
+}
+
+pub struct ExprDisplay<'a> {
+    ast: &'a Ast,
+    id: ExprId,
+}
+
+impl fmt::Display for ExprDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |id| self.ast.display(id);
+        match self.ast.expr(self.id) {
+            Expr::Binary {
+                left,
+                operator, //Operator from above grammar
+                right,
+            } => {
+                write!(f, "({} {} {})", operator.lexeme, show(*left), show(*right))
+            }
+            Expr::Grouping { expression } => {
+                write!(f, "(group {})", show(*expression))
+            }
+            Expr::Literal { value } => {
+                write!(f, "{}", value)
+            }
+            Expr::Unary { operator, right } => {
+                write!(f, "({} {})", operator.lexeme, show(*right))
+            }
+            Expr::Variable { name } => write!(f, "(variable : {})", name.lexeme),
+            Expr::Assign { name, value } => {
+                write!(f, "(Assignment : {} = {})", name.lexeme, show(*value))
+            }
+            Expr::Index { object, index, .. } => {
+                write!(f, "(Index : {}[{}])", show(*object), show(*index))
+            }
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                let arguments: Vec<String> = arguments
+                    .iter()
+                    .map(|argument| show(*argument).to_string())
+                    .collect();
+                write!(f, "(Call : {}({}))", show(*callee), arguments.join(", "))
+            }
+            Expr::Get { object, name } => {
+                write!(f, "(Get : {}.{})", show(*object), name.lexeme)
+            }
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            } => {
+                write!(
+                    f,
+                    "(Logical : {} {} {})",
+                    show(*left),
+                    operator.lexeme,
+                    show(*right)
+                )
+            }
+        }
+    }
+}
+
+// what tooling knows about nodes, like resolver results or types, indexed by node id
+pub struct SideTable<I, T> {
+    values: Vec<Option<T>>,
+    id: PhantomData<I>,
+}
+
+impl<I, T> Default for SideTable<I, T> {
+    fn default() -> Self {
+        SideTable {
+            values: Vec::new(),
+            id: PhantomData,
+        }
+    }
+}
+
+impl<I: NodeId, T> SideTable<I, T> {
+    pub fn insert(&mut self, id: I, value: T) {
+        let index = id.index();
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+        self.values[index] = Some(value);
+    }
+
+    pub fn get(&self, id: I) -> Option<&T> {
+        self.values.get(id.index()).and_then(Option::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    fn expression(ast: &Ast) -> ExprId {
+        match ast.stmt(ast.statements[0]) {
+            Stmt::Expression { expression } => *expression,
+            _ => unreachable!("only expression statements are tested"),
+        }
+    }
+
+    #[test]
+    fn expressions_display_as_trees() {
+        let ast = parse_source("x = -(1 + a[0]) * f(2, s.upper()) or !b;");
+        assert_eq!(
+            ast.display(expression(&ast)).to_string(),
+            "(Assignment : x = (Logical : (* (- (group (+ 1 (Index : (variable : a)[0])))) \
+             (Call : (variable : f)(2, (Call : (Get : (variable : s).upper)())))) or (! (variable : b))))"
+        );
+    }
+
+    #[test]
+    fn spans_cover_the_source_of_a_node() {
+        let source = "print 1 + 2;";
+        let ast = parse_source(source);
+        let span = ast.stmt_span(ast.statements[0]);
+        assert_eq!(&source[span.start..span.end], source);
+        let sum = match ast.stmt(ast.statements[0]) {
+            Stmt::Print { expression, .. } => *expression,
+            _ => unreachable!(),
+        };
+        let span = ast.expr_span(sum);
+        assert_eq!(&source[span.start..span.end], "1 + 2");
+    }
+
+    #[test]
+    fn side_tables_hold_values_by_id() {
+        let ast = parse_source("1 + 2;");
+        let sum = expression(&ast);
+        let mut table = SideTable::default();
+        assert!(table.get(sum).is_none());
+        table.insert(sum, "sum");
+        assert_eq!(table.get(sum), Some(&"sum"));
+        if let Expr::Binary { left, .. } = ast.expr(sum) {
+            assert!(table.get(*left).is_none());
+        }
+    }
+}
@@ -1,76 +1,66 @@
-use crate::scanner::{object::Object, token::Token};
-use std::fmt;
+use super::ast::ExprId;
+use crate::{
+    messages::{Code, Message},
+    scanner::{
+        object::Object,
+        token::{Token, Tokentype},
+    },
+};
 
 // following enum implements store for this cfg
 // expression → literal  | unary  | binary  | grouping ;
 // literal → NUMBER | STRING | "true" | "false" | "nil" ;
 // grouping → "(" expression ")" ;
 // unary → ( "-" | "!" ) expression ;
+// index → expression "[" expression "]" ;
+// call → expression "(" ( expression ( "," expression )* )? ")" ;
+// get → expression "." IDENTIFIER ;
 // binary → expression operator expression ;
 // operator → "==" | "!=" | "<" | "<=" | ">" | ">=" | "+" | "-" | "*" | "/" ;
 
 #[derive(Clone, PartialEq)]
 pub enum Expr {
     Binary {
-        left: Box<Expr>,
+        left: ExprId,
         operator: Token,
-        right: Box<Expr>,
+        right: ExprId,
     },
     Grouping {
-        expression: Box<Expr>,
+        expression: ExprId,
     },
     Literal {
         value: Object,
     },
     Logical {
-        left: Box<Expr>,
+        left: ExprId,
         operator: Token,
-        right: Box<Expr>,
+        right: ExprId,
     },
     Unary {
         operator: Token,
-        right: Box<Expr>,
+        right: ExprId,
     },
     Variable {
         name: Token,
     },
     Assign {
         name: Token,
-        value: Box<Expr>,
+        value: ExprId,
+    },
+    Index {
+        object: ExprId,
+        bracket: Token,
+        index: ExprId,
+    },
+    Call {
+        callee: ExprId,
+        paren: Token,
+        arguments: Vec<ExprId>,
+    },
+    Get {
+        object: ExprId,
+        name: Token,
     },
-}
-
-// needed for debug later if i am not lucky
-impl fmt::Display for Expr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Expr::Binary {
-                left,
-                operator, //Operator from above grammar
-                right,
-            } => {
-                write!(f, "({} {} {})", operator, left, right)
-            }
-            Expr::Grouping { expression } => {
-                write!(f, "(group {})", expression)
-            }
-            Expr::Literal { value } => {
-                write!(f, "{}", value)
-            }
-            Expr::Unary { operator, right } => {
-                write!(f, "({} {})", operator, right)
-            }
-            Expr::Variable { name } => write!(f, "(variable : {})", name),
-            Expr::Assign { name, value } => write!(f, "(Assignment : {} = {})", name, value),
-            Expr::Logical {
-                left,
-                operator,
-                right,
-            } => {
-                write!(f, "(Logical : {} {} {})", left, operator, right)
-            }
-        }
-    }
 }
 
 // variation from lox i want int 0 ,float 0 and strlen 0 to be false and everything else to be true
@@ -94,12 +84,20 @@ pub fn is_truthy(object: &Object) -> Object {
             }
         }
         Object::StringValue(str) => {
-            if str.len() == 0 {
+            if str.is_empty() {
+                Object::False
+            } else {
+                Object::True
+            }
+        }
+        Object::List(values) => {
+            if values.borrow().is_empty() {
                 Object::False
             } else {
                 Object::True
             }
         }
+        Object::Native(_) => Object::True,
     }
 }
 
@@ -117,3 +115,94 @@ pub fn bool(val: bool) -> Object {
         Object::False
     }
 }
+
+// what an unary operator does to its value, shared by the interpreter and the optimizer
+pub fn apply_unary(operator: Tokentype, right: Object) -> Result<Object, Message> {
+    let value = match operator {
+        Tokentype::Bang => {
+            let truthy = is_truthy(&right);
+            if truthy == Object::True {
+                Object::False
+            } else {
+                Object::True
+            }
+        }
+        Tokentype::Minus => match right {
+            Object::IntValue(value) => Object::IntValue(value.checked_neg().ok_or_else(overflow)?),
+            Object::FloatValue(value) => Object::FloatValue(-value),
+            _ => Object::Null,
+        },
+        _ => Object::Null,
+    };
+    Ok(value)
+}
+
+// what a binary operator does to its values, shared by the interpreter and the optimizer
+pub fn apply_binary(operator: Tokentype, left: Object, right: Object) -> Result<Object, Message> {
+    if let (Object::IntValue(lhs), Object::IntValue(rhs)) = (&left, &right) {
+        let checked = match operator {
+            Tokentype::Plus => lhs.checked_add(*rhs),
+            Tokentype::Minus => lhs.checked_sub(*rhs),
+            Tokentype::Star => lhs.checked_mul(*rhs),
+            _ => Some(0),
+        };
+        checked.ok_or_else(overflow)?;
+    }
+    // matchception begins here ;) good luck understanding code
+    // changed my mind writing clean code ;)
+    let value = match operator {
+        Tokentype::Minus => left - right,
+        Tokentype::Plus => left + right,
+        Tokentype::Slash => left / right,
+        Tokentype::Star => left * right,
+        Tokentype::Greater => bool(left > right),
+        Tokentype::GreaterEqual => bool(left >= right),
+        Tokentype::Less => bool(left < right),
+        Tokentype::LessEqual => bool(left <= right),
+        Tokentype::EqualEqual => bool(left == right),
+        Tokentype::BangEqual => bool(left != right),
+        _ => Object::Null,
+    };
+    Ok(value)
+}
+
+fn overflow() -> Message {
+    Message::new(Code::ArithmeticOverflow, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflows(result: Result<Object, Message>) -> bool {
+        matches!(result, Err(message) if message.code == Code::ArithmeticOverflow)
+    }
+
+    #[test]
+    fn int_overflow_is_an_error() {
+        let (max, min) = (Object::IntValue(i64::MAX), Object::IntValue(i64::MIN));
+        let one = Object::IntValue(1);
+        assert!(overflows(apply_binary(
+            Tokentype::Plus,
+            max.clone(),
+            one.clone()
+        )));
+        assert!(overflows(apply_binary(
+            Tokentype::Minus,
+            min.clone(),
+            one.clone()
+        )));
+        assert!(overflows(apply_binary(
+            Tokentype::Star,
+            max.clone(),
+            Object::IntValue(2)
+        )));
+        assert!(overflows(apply_unary(Tokentype::Minus, min.clone())));
+        // floats and other operators don't overflow
+        let float = apply_binary(Tokentype::Plus, max.clone(), Object::FloatValue(1.0));
+        assert!(float.ok() == Some(Object::FloatValue(i64::MAX as f64 + 1.0)));
+        let ratio = apply_binary(Tokentype::Slash, min, Object::IntValue(-1));
+        assert!(ratio.ok() == Some(Object::FloatValue(-(i64::MIN as f64))));
+        assert!(apply_binary(Tokentype::Less, max, one).ok() == Some(Object::False));
+    }
+}
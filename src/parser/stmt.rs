@@ -1,29 +1,32 @@
+use super::ast::{ExprId, StmtId};
 use crate::token::Token;
-
-use super::expr::Expr;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub enum Stmt {
     Expression {
-        expression: Expr,
+        expression: ExprId,
     },
     Print {
-        expression: Expr,
+        keyword: Token,
+        expression: ExprId,
     },
     Var {
         name: Token,
-        initalizer: Expr,
+        initalizer: ExprId,
+        doc: Option<Rc<str>>,
     },
     If {
-        condition: Expr,
-        then_branch: Box<Stmt>,
-        else_branch: Box<Option<Stmt>>,
+        condition: ExprId,
+        then_branch: StmtId,
+        else_branch: Option<StmtId>,
     },
     While {
-        condition: Expr,
-        body: Box<Stmt>,
+        keyword: Token,
+        condition: ExprId,
+        body: StmtId,
     },
     Block {
-        statements: Vec<Stmt>,
+        statements: Vec<StmtId>,
     },
 }
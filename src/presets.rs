@@ -0,0 +1,31 @@
+// .tokenfile presets compiled into the binary so they work from any directory
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub rules: &'static str,
+}
+
+pub const PRESETS: [Preset; 3] = [
+    Preset {
+        name: "BhaiLang",
+        description: "keywords of bhai-lang, hi bhai to bye bhai",
+        rules: include_str!("../presets/BhaiLang/.tokenfile"),
+    },
+    Preset {
+        name: "Pirate",
+        description: "talk like a pirate, shout what ye booty be",
+        rules: include_str!("../presets/Pirate/.tokenfile"),
+    },
+    Preset {
+        name: "Doge",
+        description: "such language, very wow",
+        rules: include_str!("../presets/Doge/.tokenfile"),
+    },
+];
+
+// preset names are matched case insensitively, --preset bhailang works
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
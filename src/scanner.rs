@@ -1,68 +1,70 @@
+pub mod dialect;
+pub mod error;
 pub mod object;
+pub mod symbol;
 pub mod token;
+pub mod tokenfile;
+use crate::{diagnostic::SourceFile, error::RloxError};
+use dialect::Dialect;
+use error::{ScanError, ScanErrorKind};
 use object::Object;
-use std::{collections::HashMap, fs, process};
+use std::rc::Rc;
+use symbol::Symbol;
 use token::{Token, Tokentype};
 
+// start and current are byte offsets into source, column counts characters
 pub struct Scanner {
-    source: String,
+    source: Rc<SourceFile>,
     pub tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
-    keywords: HashMap<String, Tokentype>,
+    column: usize,
+    start_line: usize,
+    start_column: usize,
+    dialect: Rc<Dialect>,
+    errors: Vec<ScanError>,
+    docs: Vec<String>,
 }
 
 impl Scanner {
-    pub fn new(source: String) -> Self {
-        let mut map: HashMap<String, Tokentype> = HashMap::from([
-            (String::from("and"), Tokentype::And),
-            (String::from("class"), Tokentype::Class),
-            (String::from("else"), Tokentype::Else),
-            (String::from("false"), Tokentype::False),
-            (String::from("for"), Tokentype::For),
-            (String::from("fun"), Tokentype::Fun),
-            (String::from("if"), Tokentype::If),
-            (String::from("nil"), Tokentype::Nil),
-            (String::from("or"), Tokentype::Or),
-            (String::from("print"), Tokentype::Print),
-            (String::from("return"), Tokentype::Return),
-            (String::from("super"), Tokentype::Super),
-            (String::from("this"), Tokentype::This),
-            (String::from("true"), Tokentype::True),
-            (String::from("var"), Tokentype::Var),
-            (String::from("while"), Tokentype::While),
-        ]);
-
-        map = read_tokenfile(map);
-
+    pub fn new(source: Rc<SourceFile>, dialect: Rc<Dialect>) -> Self {
         Self {
             source,
             tokens: vec![],
             start: 0,
             current: 0,
             line: 1,
-            keywords: map,
+            column: 1,
+            start_line: 1,
+            start_column: 1,
+            dialect,
+            errors: vec![],
+            docs: vec![],
         }
     }
 }
 
 impl Scanner {
-    pub fn scan_tokens(&mut self) -> &Vec<Token> {
+    pub fn scan_tokens(&mut self) -> Result<&Vec<Token>, RloxError> {
         while !self.is_at_end() {
             // at the beginning of the next lexeme.
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.column;
             self.scan_token();
         }
 
-        self.tokens.push(Token::new(
-            Tokentype::Eof,
-            String::from(""),
-            Object::Null,
-            self.line,
-        ));
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
+        self.add_token(Tokentype::Eof);
 
-        &self.tokens
+        if self.errors.is_empty() {
+            Ok(&self.tokens)
+        } else {
+            Err(RloxError::Scan(std::mem::take(&mut self.errors)))
+        }
     }
 
     fn scan_token(&mut self) {
@@ -73,6 +75,8 @@ impl Scanner {
             ')' => self.add_token(Tokentype::RightParen),
             '{' => self.add_token(Tokentype::LeftBrace),
             '}' => self.add_token(Tokentype::RightBrace),
+            '[' => self.add_token(Tokentype::LeftBracket),
+            ']' => self.add_token(Tokentype::RightBracket),
             ',' => self.add_token(Tokentype::Comma),
             '.' => self.add_token(Tokentype::Dot),
             '-' => self.add_token(Tokentype::Minus),
@@ -82,13 +86,15 @@ impl Scanner {
             // multi character tokens
             '/' => {
                 if self.check_next_char("/") {
-                    // deal with comments
-                    // let mut comment = String::from("");
-                    // while self.peek() != "\n" && !self.is_at_end() {
-                    //     let char = self.advance();
-                    //     comment.push(char);
-                    // }
-                    // println!("comment is : {}", comment)
+                    // "///" starts a doc comment but "////" is a plain comment again
+                    if self.dialect.features.doc_comments
+                        && self.peek() == "/"
+                        && self.peek_next() != '/'
+                    {
+                        self.doc_comment();
+                    } else {
+                        self.line_comment();
+                    }
                 } else if self.check_next_char("*") {
                     self.multi_line_comment();
                 } else {
@@ -107,14 +113,14 @@ impl Scanner {
             ' ' => {}
             '\r' => {}
             '\t' => {}
-            '\n' => self.line += 1,
+            '\n' => self.newline(),
             _ => {
                 if is_digit(character) {
                     self.number()
                 } else if is_alpha(character) {
                     self.identifier();
                 } else {
-                    println!("Unexpected token.")
+                    self.error(ScanErrorKind::UnexpectedCharacter(character));
                 }
             }
         }
@@ -125,29 +131,60 @@ impl Scanner {
 impl Scanner {
     // checks if code ended
     fn is_at_end(&self) -> bool {
-        self.current >= self.source.len()
+        self.current >= self.source.text.len()
     }
 
     // moves 1 character ahead in source and returns current character
     fn advance(&mut self) -> char {
-        self.current += 1;
-        match self.source.chars().nth(self.current - 1) {
-            Some(character) => character,
+        match self.source.text[self.current..].chars().next() {
+            Some(character) => {
+                self.current += character.len_utf8();
+                self.column += 1;
+                character
+            }
             None => '\0',
         }
     }
 
+    // call after consuming a '\n'
+    fn newline(&mut self) {
+        self.line += 1;
+        self.column = 1;
+    }
+
+    // records an error spanning the current lexeme, scanning carries on to report every error
+    fn error(&mut self, kind: ScanErrorKind) {
+        self.errors.push(ScanError {
+            message: kind.message(),
+            source: self.source.clone(),
+            line: self.start_line,
+            column: self.start_column,
+            offset: self.start,
+            len: self.source.text[self.start..self.current].chars().count(),
+        });
+    }
+
     // adds the token provided to tokens list
     fn add_token(&mut self, tokentype: Tokentype) {
-        let text = self.source[self.start..self.current].to_string();
-        let token = Token::new(tokentype, text, Object::Null, self.line);
-        self.tokens.push(token);
+        self.add_token_with_literal(tokentype, Object::Null);
     }
 
     // adds token with its literal to the list
     fn add_token_with_literal(&mut self, tokentype: Tokentype, literal: Object) {
-        let text = self.source[self.start..self.current].to_string();
-        let token = Token::new(tokentype, text, literal, self.line);
+        let text = Symbol::new(&self.source.text[self.start..self.current]);
+        let mut token = Token::new(
+            tokentype,
+            text,
+            literal,
+            self.start_line,
+            self.start_column,
+            self.start,
+            self.source.clone(),
+        );
+        if !self.docs.is_empty() {
+            token.doc = Some(Rc::from(self.docs.join("\n")));
+            self.docs.clear();
+        }
         self.tokens.push(token);
     }
 
@@ -165,11 +202,11 @@ impl Scanner {
         if self.is_at_end() {
             return false;
         };
-        if self.source.chars().nth(self.current).unwrap().to_string() != expected {
+        if self.peek() != expected {
             return false;
         }
 
-        self.current += 1;
+        self.advance();
         true
     }
 
@@ -178,29 +215,36 @@ impl Scanner {
         if self.is_at_end() {
             return String::from("\0");
         }
-        self.source.chars().nth(self.current).unwrap().to_string()
+        self.source.text[self.current..]
+            .chars()
+            .next()
+            .unwrap()
+            .to_string()
     }
 
     // just check next character dont consume
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
-            '\0'
-        } else {
-            self.source.chars().nth(self.current + 1).unwrap()
-        }
+        self.peek_nth(1)
+    }
+
+    // looks n characters past the current one without consuming anything
+    fn peek_nth(&self, n: usize) -> char {
+        self.source.text[self.current..]
+            .chars()
+            .nth(n)
+            .unwrap_or('\0')
     }
 
     // utility to find string literals in source
     fn string(&mut self) {
         while self.peek().chars().nth(0).unwrap() != '"' && !self.is_at_end() {
-            if self.peek() == "\n" {
-                self.line += 1;
+            if self.advance() == '\n' {
+                self.newline();
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            println!("Unterminated String starting at line {}", self.line);
+            self.error(ScanErrorKind::UnterminatedString);
             return;
         }
 
@@ -208,90 +252,391 @@ impl Scanner {
         self.advance();
 
         // adding token
-        let value = self.source[self.start + 1..self.current - 1].to_string();
-        self.add_token_with_literal(Tokentype::String, Object::StringValue(value));
+        let value = self.source.text[self.start + 1..self.current - 1].to_string();
+        self.add_token_with_literal(Tokentype::String, Object::StringValue(value.into()));
+    }
+
+    fn line_comment(&mut self) {
+        while self.peek() != "\n" && !self.is_at_end() {
+            self.advance();
+        }
+    }
+
+    // doc comments are kept and handed to the next token so the parser can attach them to declarations
+    fn doc_comment(&mut self) {
+        self.advance(); // third /
+        let from = self.current;
+        self.line_comment();
+        let text = self.source.text[from..self.current].trim_end_matches('\r');
+        let text = text.strip_prefix(' ').unwrap_or(text);
+        self.docs.push(text.to_string());
     }
 
+    // block comments nest so code that already has a block comment can be commented out
     fn multi_line_comment(&mut self) {
-        while self.peek() != "*" && self.peek_next() != '/' && !self.is_at_end() {
-            if self.peek() == "\n" {
-                self.line += 1;
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_at_end() {
+                self.error(ScanErrorKind::UnterminatedComment);
+                return;
+            }
+            if self.dialect.features.nested_comments
+                && self.peek() == "/"
+                && self.peek_next() == '*'
+            {
+                self.advance();
+                self.advance();
+                depth += 1;
+            } else if self.peek() == "*" && self.peek_next() == '/' {
+                self.advance();
+                self.advance();
+                depth -= 1;
+            } else if self.advance() == '\n' {
+                self.newline();
             }
-            self.advance();
         }
-        self.advance(); // at last *
-        self.advance(); // consuming last /
     }
 
     // utility to find number literal
+    // decimal 1_000, 1.5, 6.02e23, 1e-9 and prefixed 0xFF, 0b1010, 0o755 literals are supported
     fn number(&mut self) {
-        while is_digit(self.peek().chars().nth(0).unwrap()) {
-            self.advance();
+        let radix = if &self.source.text[self.start..self.current] == "0" {
+            match self.peek().as_str() {
+                "x" | "X" => 16,
+                "o" | "O" => 8,
+                "b" | "B" => 2,
+                _ => 10,
+            }
+        } else {
+            10
+        };
+
+        if radix != 10 {
+            self.advance(); // base prefix
+            self.digits(radix);
+        } else {
+            self.digits(10);
+            if self.peek() == "." && is_digit(self.peek_next()) {
+                self.advance();
+                self.digits(10);
+            }
+            let sign = matches!(self.peek_next(), '+' | '-');
+            if matches!(self.peek().as_str(), "e" | "E")
+                && (is_digit(self.peek_next()) || (sign && is_digit(self.peek_nth(2))))
+            {
+                self.advance(); // e
+                if sign {
+                    self.advance();
+                }
+                self.digits(10);
+            }
         }
-        if self.peek() == "." && is_digit(self.peek_next()) {
-            self.advance();
-            while is_digit(self.peek().chars().nth(0).unwrap()) {
+        // a number running straight into letters or digits of another base like 12abc or 0b12 is not a number
+        if is_alpha_numeric(self.peek().chars().nth(0).unwrap()) {
+            while is_alpha_numeric(self.peek().chars().nth(0).unwrap()) {
                 self.advance();
             }
-            let float = self.source[self.start..self.current]
-                .parse()
-                .expect("number out of range");
-            self.add_token_with_literal(Tokentype::Number, Object::FloatValue(float));
+            let lexeme = self.source.text[self.start..self.current].to_string();
+            self.error(ScanErrorKind::InvalidNumber(lexeme));
             return;
         }
         // adding token
-        let integer = self.source[self.start..self.current]
-            .parse()
-            .expect("number out of range");
-        self.add_token_with_literal(Tokentype::Number, Object::IntValue(integer))
+        let lexeme = &self.source.text[self.start..self.current];
+        match parse_number(lexeme, radix) {
+            Some(literal) => self.add_token_with_literal(Tokentype::Number, literal),
+            None => {
+                let lexeme = lexeme.to_string();
+                self.error(ScanErrorKind::InvalidNumber(lexeme));
+            }
+        }
+    }
+
+    // consumes digits of the given radix along with '_' separators
+    fn digits(&mut self, radix: u32) {
+        loop {
+            let character = self.peek().chars().nth(0).unwrap();
+            if (character == '_' && self.dialect.features.digit_separators)
+                || character.is_digit(radix)
+            {
+                self.advance();
+            } else {
+                break;
+            }
+        }
     }
 
     fn identifier(&mut self) {
         while is_alpha_numeric(self.peek().chars().nth(0).unwrap()) {
             self.advance();
         }
-        let text: String = self.source[self.start..self.current].to_string();
-        match self.keywords.get(&text) {
+        if let Some(tokentype) = self.phrase() {
+            self.add_token(tokentype);
+            return;
+        }
+        let text: String = self.source.text[self.start..self.current].to_string();
+        match self.dialect.keywords.words.get(&text) {
             Some(result) => self.add_token(*result),
             _ => self.add_token(Tokentype::Identifier),
         };
     }
+
+    // greedily matches a multi word alias starting with the word just scanned,
+    // on success the whole phrase becomes the lexeme
+    fn phrase(&mut self) -> Option<Tokentype> {
+        let first = &self.source.text[self.start..self.current];
+        let dialect = self.dialect.clone();
+        for (words, tokentype) in dialect.keywords.phrases.get(first)? {
+            let (current, line, column) = (self.current, self.line, self.column);
+            if words[1..].iter().all(|word| self.next_word() == *word) {
+                return Some(*tokentype);
+            }
+            (self.current, self.line, self.column) = (current, line, column);
+        }
+        None
+    }
+
+    // skips whitespace and consumes the following identifier like word if any
+    fn next_word(&mut self) -> &str {
+        while matches!(self.peek().as_str(), " " | "\t" | "\r" | "\n") {
+            if self.advance() == '\n' {
+                self.newline();
+            }
+        }
+        let from = self.current;
+        if is_alpha(self.peek().chars().nth(0).unwrap()) {
+            while is_alpha_numeric(self.peek().chars().nth(0).unwrap()) {
+                self.advance();
+            }
+        }
+        &self.source.text[from..self.current]
+    }
 }
 
 // utility to check if current character is digit
 pub fn is_digit(character: char) -> bool {
-    character >= '0' && character <= '9'
+    character.is_ascii_digit()
 }
 
 pub fn is_alpha(character: char) -> bool {
-    (character >= 'a' && character <= 'z')
-        || (character >= 'A' && character <= 'Z')
-        || character == '_'
+    character.is_ascii_alphabetic() || character == '_'
 }
 
 pub fn is_alpha_numeric(character: char) -> bool {
     is_alpha(character) || is_digit(character)
 }
 
-fn read_tokenfile(mut map: HashMap<String, Tokentype>) -> HashMap<String, Tokentype> {
-    match fs::read_to_string(".tokenfile") {
-        Ok(content) => {
-            let rules: Vec<String> = content.lines().map(|line| line.to_string()).collect();
-            for rule in rules {
-                let temp: Vec<&str> = rule.split(':').collect();
-                match map.get(temp[0].trim()) {
-                    Some(token) => {
-                        map.insert(temp[1].trim().to_string(), *token);
-                        let _ = map.remove(temp[0].trim());
-                    }
-                    None => {
-                        println!("Token specified in .tokenfile not found.");
-                        process::exit(64);
-                    }
-                };
+// reads a whole string written as a number literal with an optional sign, for parse_number()
+pub fn parse_literal(text: &str) -> Option<Object> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let radix = match unsigned.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    let digits = if radix == 10 {
+        unsigned
+    } else {
+        &unsigned[2..]
+    };
+    if !digits.starts_with(|c: char| c.is_digit(radix))
+        || !digits.ends_with(|c: char| c.is_digit(radix))
+    {
+        return None;
+    }
+    // rust reads the sign itself which keeps the smallest int in range
+    parse_number(
+        if radix == 10 || negative {
+            text
+        } else {
+            unsigned
+        },
+        radix,
+    )
+}
+
+// converts a number lexeme to its value, None if it is malformed or out of range
+fn parse_number(lexeme: &str, radix: u32) -> Option<Object> {
+    // only parse_literal passes a sign, scanned numbers are unsigned
+    let (sign, lexeme) = match lexeme.strip_prefix('-') {
+        Some(unsigned) if radix != 10 => ("-", unsigned),
+        _ => ("", lexeme),
+    };
+    let digits = if radix == 10 { lexeme } else { &lexeme[2..] };
+    // '_' is only allowed between two digits
+    let characters: Vec<char> = digits.chars().collect();
+    for (index, character) in characters.iter().enumerate() {
+        if *character == '_' {
+            let before = index.checked_sub(1).map(|i| characters[i]);
+            let after = characters.get(index + 1);
+            if !before.is_some_and(|c| c.is_digit(radix))
+                || !after.is_some_and(|c| c.is_digit(radix))
+            {
+                return None;
             }
         }
-        Err(_) => (),
     }
-    map
+    let digits = sign.to_string() + &digits.replace('_', "");
+
+    if radix != 10 {
+        i64::from_str_radix(&digits, radix)
+            .ok()
+            .map(Object::IntValue)
+    } else if digits.contains(['.', 'e', 'E']) {
+        digits
+            .parse::<f64>()
+            .ok()
+            .filter(|float| float.is_finite())
+            .map(Object::FloatValue)
+    } else {
+        digits.parse().ok().map(Object::IntValue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Code;
+
+    // the literal of the number source starts with, or the code of the first scan error
+    fn number(source: &str) -> Result<Object, Code> {
+        let source = SourceFile::new("test", source.to_string());
+        let mut scanner = Scanner::new(source, Rc::new(Dialect::default()));
+        match scanner.scan_tokens() {
+            Ok(tokens) => Ok(tokens[0].literal.clone()),
+            Err(RloxError::Scan(errors)) => Err(errors[0].message.code),
+            Err(_) => unreachable!("scanning only fails with scan errors"),
+        }
+    }
+
+    // line and column of every token source scans to
+    fn positions(source: &str) -> Vec<(usize, usize)> {
+        let source = SourceFile::new("test", source.to_string());
+        let mut scanner = Scanner::new(source, Rc::new(Dialect::default()));
+        let tokens = scanner.scan_tokens().ok().unwrap();
+        tokens
+            .iter()
+            .map(|token| (token.line, token.column))
+            .collect()
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        assert_eq!(
+            positions("\"ü\" + x;\n  /* ü */ y"),
+            [(1, 1), (1, 5), (1, 7), (1, 8), (2, 11), (2, 12)]
+        );
+    }
+
+    // the types of the tokens source scans to with a dialect built from rules, or the code of
+    // the first scan error
+    fn scan(source: &str, rules: &str) -> Result<Vec<Tokentype>, Code> {
+        let source = SourceFile::new("test", source.to_string());
+        let dialect = Dialect::from_rules(rules).ok().unwrap();
+        let mut scanner = Scanner::new(source, Rc::new(dialect));
+        match scanner.scan_tokens() {
+            Ok(tokens) => Ok(tokens.iter().map(|token| token.tokentype).collect()),
+            Err(RloxError::Scan(errors)) => Err(errors[0].message.code),
+            Err(_) => unreachable!("scanning only fails with scan errors"),
+        }
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        let print = Ok(vec![Tokentype::Print, Tokentype::Number, Tokentype::Eof]);
+        assert!(scan("/* a /* b */ c */ print 1", "") == print);
+        assert!(scan("/* a * b / c */ print 1", "") == print);
+        assert!(scan("/*/**/*/ print 1", "") == print);
+        let flat = "[features]\nnested_comments : off";
+        assert!(scan("/* a /* b */ print 1", flat) == print);
+    }
+
+    #[test]
+    fn unterminated_block_comments_are_errors() {
+        assert!(scan("print 1; /* open", "") == Err(Code::UnterminatedComment));
+        assert!(scan("/* a /* b */ print 1;", "") == Err(Code::UnterminatedComment));
+        assert!(scan("/* a *", "") == Err(Code::UnterminatedComment));
+    }
+
+    #[test]
+    fn doc_comments_are_attached_to_declarations() {
+        let ast = crate::parser::parse_source(
+            "/// the answer\n///  to everything\nvar answer = 42;\n//// not a doc\nvar x;",
+        );
+        let docs: Vec<Option<&str>> = ast
+            .statements
+            .iter()
+            .map(|statement| match ast.stmt(*statement) {
+                crate::parser::stmt::Stmt::Var { doc, .. } => doc.as_deref(),
+                _ => unreachable!("only declarations are tested"),
+            })
+            .collect();
+        assert_eq!(docs, [Some("the answer\n to everything"), None]);
+    }
+
+    #[test]
+    fn reads_every_base() {
+        assert!(number("255") == Ok(Object::IntValue(255)));
+        assert!(number("0xFF") == Ok(Object::IntValue(255)));
+        assert!(number("0XfF") == Ok(Object::IntValue(255)));
+        assert!(number("0o17") == Ok(Object::IntValue(15)));
+        assert!(number("0b101") == Ok(Object::IntValue(5)));
+        assert!(number("1.5") == Ok(Object::FloatValue(1.5)));
+    }
+
+    #[test]
+    fn separators_go_between_digits() {
+        assert!(number("1_000") == Ok(Object::IntValue(1000)));
+        assert!(number("0xFF_FF") == Ok(Object::IntValue(0xFFFF)));
+        assert!(number("1_000.5") == Ok(Object::FloatValue(1000.5)));
+        assert!(number("1__0") == Err(Code::InvalidNumber));
+        assert!(number("1_") == Err(Code::InvalidNumber));
+        assert!(number("0x_1") == Err(Code::InvalidNumber));
+        assert!(number("1_.5") == Err(Code::InvalidNumber));
+    }
+
+    #[test]
+    fn prefixes_need_digits() {
+        assert!(number("0x") == Err(Code::InvalidNumber));
+        assert!(number("0b") == Err(Code::InvalidNumber));
+        assert!(number("0b12") == Err(Code::InvalidNumber));
+        assert!(number("0o8") == Err(Code::InvalidNumber));
+        assert!(number("12abc") == Err(Code::InvalidNumber));
+    }
+
+    #[test]
+    fn exponents() {
+        assert!(number("1e3") == Ok(Object::FloatValue(1000.0)));
+        assert!(number("1.5e-3") == Ok(Object::FloatValue(0.0015)));
+        assert!(number("2E+2") == Ok(Object::FloatValue(200.0)));
+        assert!(number("1e") == Err(Code::InvalidNumber));
+        assert!(number("1e+") == Err(Code::InvalidNumber));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(number("9223372036854775807") == Ok(Object::IntValue(i64::MAX)));
+        assert!(number("9223372036854775808") == Err(Code::InvalidNumber));
+        assert!(number("0x7FFFFFFFFFFFFFFF") == Ok(Object::IntValue(i64::MAX)));
+        assert!(number("0x8000000000000000") == Err(Code::InvalidNumber));
+        assert!(number("1e999") == Err(Code::InvalidNumber));
+    }
+
+    #[test]
+    fn literals_with_a_sign() {
+        assert!(parse_literal("-9223372036854775808") == Some(Object::IntValue(i64::MIN)));
+        assert!(parse_literal("-0x10") == Some(Object::IntValue(-16)));
+        assert!(parse_literal("+5") == Some(Object::IntValue(5)));
+        assert!(parse_literal("-0x8000000000000000") == Some(Object::IntValue(i64::MIN)));
+        assert!(parse_literal("-0b1") == Some(Object::IntValue(-1)));
+        assert!(parse_literal("0x8000000000000000").is_none());
+        assert!(parse_literal("-0x8000000000000001").is_none());
+        assert!(parse_literal("0x").is_none());
+        assert!(parse_literal("1_").is_none());
+        assert!(parse_literal("five").is_none());
+        assert!(parse_literal(" 5").is_none());
+    }
 }
@@ -0,0 +1,183 @@
+use super::{
+    token::Tokentype,
+    tokenfile::{config_error, Keywords},
+};
+use crate::{error::RloxError, messages::Messages};
+use std::{fs, path::Path};
+
+// parts of the scanner a dialect can switch off
+pub struct Features {
+    pub nested_comments: bool,
+    pub doc_comments: bool,
+    pub digit_separators: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features {
+            nested_comments: true,
+            doc_comments: true,
+            digit_separators: true,
+        }
+    }
+}
+
+// everything the scanner needs to know about the language it scans,
+// built once from a .tokenfile and shared by every scanner using it
+pub struct Dialect {
+    pub keywords: Keywords,
+    pub features: Features,
+    pub messages: Messages,
+}
+
+impl Default for Dialect {
+    // plain lox
+    fn default() -> Self {
+        Dialect {
+            keywords: Keywords::new(&[])
+                .unwrap_or_else(|_| unreachable!("plain lox has no rules to get wrong")),
+            features: Features::default(),
+            messages: Messages::default(),
+        }
+    }
+}
+
+impl Dialect {
+    // rules is the content of a .tokenfile:
+    //
+    // # a '#' starting a line or following whitespace starts a comment
+    // print : bol bhai        # keyword rules, [keywords] is the default section
+    // [features]
+    // nested_comments : off
+    // [messages]               # a '#' in a message is part of it, only whole lines are comments
+    // E0204 : arre bhai, ';' kidhar hai?
+    pub fn from_rules(rules: &str) -> Result<Self, RloxError> {
+        let mut section = "keywords";
+        let mut keywords = Vec::new();
+        let mut features = Features::default();
+        let mut messages = Messages::default();
+        for (number, line) in rules.lines().enumerate() {
+            let number = number + 1;
+            let line = match section {
+                "messages" => line.trim(),
+                _ => strip_comment(line).trim(),
+            };
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = match name.trim() {
+                    "keywords" => "keywords",
+                    "features" => "features",
+                    "messages" => "messages",
+                    name => {
+                        return Err(config_error(
+                            number,
+                            &format!("unknown section '[{}]'.", name),
+                        ))
+                    }
+                };
+                continue;
+            }
+            match section {
+                "keywords" => keywords.push((number, line)),
+                "features" => features.set(number, line)?,
+                _ => messages.set(number, line)?,
+            }
+        }
+        Ok(Dialect {
+            keywords: Keywords::new(&keywords)?,
+            features,
+            messages,
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, RloxError> {
+        match fs::read_to_string(path) {
+            Ok(rules) => Dialect::from_rules(&rules),
+            Err(msg) => Err(RloxError::NoInput(format!("{} : {}", path.display(), msg))),
+        }
+    }
+
+    // how the dialect writes a keyword or symbol
+    pub fn spelling(&self, tokentype: Tokentype) -> Option<&str> {
+        self.keywords.spelling(tokentype)
+    }
+}
+
+// a '#' at the start of a line or after whitespace starts a comment, 'E#1' is no comment
+fn strip_comment(line: &str) -> &str {
+    let mut previous = ' ';
+    for (index, character) in line.char_indices() {
+        if character == '#' && previous.is_whitespace() {
+            return &line[..index];
+        }
+        previous = character;
+    }
+    line
+}
+
+impl Features {
+    // applies a `feature : on|off` rule
+    fn set(&mut self, number: usize, rule: &str) -> Result<(), RloxError> {
+        let (name, value) = match rule.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => {
+                return Err(config_error(
+                    number,
+                    "expect 'feature : on' or 'feature : off'.",
+                ))
+            }
+        };
+        let value = match value {
+            "on" | "true" => true,
+            "off" | "false" => false,
+            _ => {
+                return Err(config_error(
+                    number,
+                    &format!("feature '{}' can only be on or off.", name),
+                ))
+            }
+        };
+        match name {
+            "nested_comments" => self.nested_comments = value,
+            "doc_comments" => self.doc_comments = value,
+            "digit_separators" => self.digit_separators = value,
+            _ => {
+                return Err(config_error(
+                    number,
+                    &format!("unknown feature '{}'.", name),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{Code, Message};
+
+    // the text of the code E0301 error for the variable x in dialect
+    fn undefined(dialect: &Dialect) -> String {
+        let mut message = Message::new(Code::UndefinedVariable, &[&"x"]);
+        message.localize(&dialect.messages);
+        message.text
+    }
+
+    #[test]
+    fn comments_start_at_a_hash_after_whitespace() {
+        let rules = "# shouting\nprint : shout # loud\n\n[features] # scanner\ndoc_comments : off";
+        let dialect = Dialect::from_rules(rules).ok().unwrap();
+        assert!(dialect.spelling(Tokentype::Print) == Some("shout"));
+        assert!(!dialect.features.doc_comments);
+    }
+
+    #[test]
+    fn messages_keep_their_hashes() {
+        let rules = "[messages]\n# whole lines are still comments\nE0301 : no #{0} here # really";
+        let dialect = Dialect::from_rules(rules).ok().unwrap();
+        assert_eq!(undefined(&dialect), "no #x here # really");
+    }
+}
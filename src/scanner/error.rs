@@ -0,0 +1,58 @@
+use crate::{
+    diagnostic::{Diagnostic, SourceFile},
+    messages::{Code, Message, Messages},
+};
+use std::{fmt, rc::Rc};
+
+pub enum ScanErrorKind {
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidNumber(String),
+}
+
+impl ScanErrorKind {
+    pub fn message(&self) -> Message {
+        match self {
+            ScanErrorKind::UnexpectedCharacter(character) => {
+                Message::new(Code::UnexpectedCharacter, &[character])
+            }
+            ScanErrorKind::UnterminatedString => Message::new(Code::UnterminatedString, &[]),
+            ScanErrorKind::UnterminatedComment => Message::new(Code::UnterminatedComment, &[]),
+            ScanErrorKind::InvalidNumber(lexeme) => Message::new(Code::InvalidNumber, &[lexeme]),
+        }
+    }
+}
+
+// an error found while scanning, located at the lexeme that caused it
+pub struct ScanError {
+    pub message: Message,
+    pub source: Rc<SourceFile>,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl ScanError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            &self.message,
+            self.source.clone(),
+            self.line,
+            self.column,
+            self.offset,
+            self.len,
+        )
+    }
+
+    pub fn localize(&mut self, messages: &Messages) {
+        self.message.localize(messages)
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
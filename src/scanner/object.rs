@@ -1,14 +1,19 @@
+use crate::interpreter::{gc::List, natives::Native};
 use std::{
+    cell::RefCell,
     cmp::Ordering,
     fmt,
     ops::{Add, Div, Mul, Sub},
+    rc::Rc,
 };
 
 #[derive(Clone)]
 pub enum Object {
     IntValue(i64),
     FloatValue(f64),
-    StringValue(String),
+    StringValue(Rc<str>),
+    List(List),
+    Native(Native),
     // _Identifier(String),
     True,
     False,
@@ -27,6 +32,8 @@ impl PartialEq for Object {
             (Object::FloatValue(val), Object::FloatValue(other)) => val == other,
             (Object::FloatValue(val), Object::IntValue(other)) => *val == *other as f64,
             (Object::StringValue(val), Object::StringValue(other)) => val == other,
+            (Object::List(val), Object::List(other)) => lists_equal(val, other, &mut Vec::new()),
+            (Object::Native(val), Object::Native(other)) => val.name == other.name,
             _ => false,
         }
     }
@@ -120,7 +127,9 @@ impl Add for Object {
                 Object::FloatValue(lhs + rhs as f64)
             }
             (Object::FloatValue(lhs), Object::FloatValue(rhs)) => Object::FloatValue(lhs + rhs),
-            (Object::StringValue(lhs), Object::StringValue(rhs)) => Object::StringValue(lhs + &rhs),
+            (Object::StringValue(lhs), Object::StringValue(rhs)) => {
+                Object::StringValue([lhs, rhs].concat().into())
+            }
             _ => Object::Null,
         }
     }
@@ -180,6 +189,8 @@ impl fmt::Display for Object {
             Object::StringValue(value) => {
                 write!(f, "{}", value)
             }
+            Object::List(values) => write_list(f, values, &mut Vec::new()),
+            Object::Native(native) => write!(f, "<native fn {}>", native.name),
             // Object::_Identifier(value) => {
             //     write!(f, "{}", value)
             // }
@@ -196,6 +207,53 @@ impl fmt::Display for Object {
     }
 }
 
+type Pair = (*const RefCell<Vec<Object>>, *const RefCell<Vec<Object>>);
+
+// lists are equal when their values are, lists that are already being compared are taken to be
+// equal so lists inside themselves don't compare forever
+fn lists_equal(list: &List, other: &List, comparing: &mut Vec<Pair>) -> bool {
+    let pair = (Rc::as_ptr(list), Rc::as_ptr(other));
+    if Rc::ptr_eq(list, other) || comparing.contains(&pair) {
+        return true;
+    }
+    comparing.push(pair);
+    let (values, others) = (list.borrow(), other.borrow());
+    let equal = values.len() == others.len()
+        && values
+            .iter()
+            .zip(others.iter())
+            .all(|(value, other)| match (value, other) {
+                (Object::List(value), Object::List(other)) => lists_equal(value, other, comparing),
+                (value, other) => value == other,
+            });
+    comparing.pop();
+    equal
+}
+
+// a list inside itself is shown as [...]
+fn write_list(
+    f: &mut fmt::Formatter<'_>,
+    list: &List,
+    open: &mut Vec<*const RefCell<Vec<Object>>>,
+) -> fmt::Result {
+    if open.contains(&Rc::as_ptr(list)) {
+        return write!(f, "[...]");
+    }
+    open.push(Rc::as_ptr(list));
+    write!(f, "[")?;
+    for (index, value) in list.borrow().iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        match value {
+            Object::List(inner) => write_list(f, inner, open)?,
+            value => write!(f, "{}", value)?,
+        }
+    }
+    open.pop();
+    write!(f, "]")
+}
+
 impl Object {
     pub fn _int(&self) -> Result<i64, &str> {
         match self {
@@ -211,4 +269,17 @@ impl Object {
             _ => Err("Expected int"),
         }
     }
+
+    // the name of the type as scripts see it
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::IntValue(_) => "int",
+            Object::FloatValue(_) => "float",
+            Object::StringValue(_) => "string",
+            Object::List(_) => "list",
+            Object::Native(_) => "function",
+            Object::True | Object::False => "bool",
+            Object::Null => "nil",
+        }
+    }
 }
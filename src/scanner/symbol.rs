@@ -0,0 +1,77 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    rc::Rc,
+};
+
+// an interned lexeme, equal names share one allocation so cloning, comparing and hashing a symbol
+// doesn't depend on how long the name is
+#[derive(Clone)]
+pub struct Symbol(Rc<str>);
+
+struct Interner {
+    names: HashSet<Rc<str>>,
+    // names nothing else holds are dropped once there are this many
+    limit: usize,
+}
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner {
+        names: HashSet::new(),
+        limit: 1024,
+    });
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Symbol {
+        INTERNER.with(|interner| {
+            let mut interner = interner.borrow_mut();
+            if let Some(name) = interner.names.get(name) {
+                return Symbol(name.clone());
+            }
+            // a long running program scans many scripts, the names of old ones can go
+            if interner.names.len() >= interner.limit {
+                interner.names.retain(|name| Rc::strong_count(name) > 1);
+                interner.limit = 1024.max(interner.names.len() * 2);
+            }
+            let name: Rc<str> = Rc::from(name);
+            interner.names.insert(name.clone());
+            Symbol(name)
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).cast::<u8>().hash(state)
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
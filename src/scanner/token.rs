@@ -1,26 +1,40 @@
-use crate::scanner::object;
-use std::fmt;
+use crate::{
+    diagnostic::SourceFile,
+    scanner::{object, symbol::Symbol},
+};
+use std::{fmt, rc::Rc};
 
 #[derive(Clone, PartialEq)]
 pub struct Token {
     pub tokentype: Tokentype,
-    pub lexeme: String,
+    pub lexeme: Symbol,
     pub literal: object::Object,
     pub line: usize,
+    pub column: usize, // 1 based, counted in characters
+    pub offset: usize, // byte offset of the lexeme in source
+    pub source: Rc<SourceFile>,
+    pub doc: Option<Rc<str>>, // text of the /// comments right before this token
 }
 
 impl Token {
     pub fn new(
         tokentype: Tokentype,
-        lexeme: String,
+        lexeme: Symbol,
         literal: object::Object,
         line: usize,
+        column: usize,
+        offset: usize,
+        source: Rc<SourceFile>,
     ) -> Token {
         Token {
             tokentype,
             lexeme,
             literal,
             line,
+            column,
+            offset,
+            source,
+            doc: None,
         }
     }
 }
@@ -31,13 +45,15 @@ impl fmt::Display for Token {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tokentype {
     // Single-character tokens.
     LeftParen,
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -86,6 +102,8 @@ impl fmt::Display for Tokentype {
             Tokentype::RightParen => write!(f, "RightParen"),
             Tokentype::LeftBrace => write!(f, "LeftBrace"),
             Tokentype::RightBrace => write!(f, "RightBrace"),
+            Tokentype::LeftBracket => write!(f, "LeftBracket"),
+            Tokentype::RightBracket => write!(f, "RightBracket"),
             Tokentype::Comma => write!(f, "Comma"),
             Tokentype::Dot => write!(f, "Dot"),
             Tokentype::Minus => write!(f, "Minus"),
@@ -0,0 +1,310 @@
+use super::{is_alpha, is_alpha_numeric, token::Tokentype};
+use crate::error::RloxError;
+use std::collections::HashMap;
+
+const KEYWORDS: [(&str, Tokentype); 16] = [
+    ("and", Tokentype::And),
+    ("class", Tokentype::Class),
+    ("else", Tokentype::Else),
+    ("false", Tokentype::False),
+    ("for", Tokentype::For),
+    ("fun", Tokentype::Fun),
+    ("if", Tokentype::If),
+    ("nil", Tokentype::Nil),
+    ("or", Tokentype::Or),
+    ("print", Tokentype::Print),
+    ("return", Tokentype::Return),
+    ("super", Tokentype::Super),
+    ("this", Tokentype::This),
+    ("true", Tokentype::True),
+    ("var", Tokentype::Var),
+    ("while", Tokentype::While),
+];
+
+// operators and punctuation that can be given aliases along with keywords
+const SYMBOLS: [(&str, Tokentype); 21] = [
+    ("(", Tokentype::LeftParen),
+    (")", Tokentype::RightParen),
+    ("{", Tokentype::LeftBrace),
+    ("}", Tokentype::RightBrace),
+    ("[", Tokentype::LeftBracket),
+    ("]", Tokentype::RightBracket),
+    (",", Tokentype::Comma),
+    (".", Tokentype::Dot),
+    ("-", Tokentype::Minus),
+    ("+", Tokentype::Plus),
+    (";", Tokentype::Semicolon),
+    ("/", Tokentype::Slash),
+    ("*", Tokentype::Star),
+    ("!", Tokentype::Bang),
+    ("!=", Tokentype::BangEqual),
+    ("=", Tokentype::Equal),
+    ("==", Tokentype::EqualEqual),
+    (">", Tokentype::Greater),
+    (">=", Tokentype::GreaterEqual),
+    ("<", Tokentype::Less),
+    ("<=", Tokentype::LessEqual),
+];
+
+// keyword aliases, multi word aliases like `bol bhai` are kept apart from single words
+pub struct Keywords {
+    pub words: HashMap<String, Tokentype>,
+    // keyed by the first word, longest phrase first
+    pub phrases: HashMap<String, Vec<(Vec<String>, Tokentype)>>,
+    // how each keyword and symbol is written, the first alias given to a token wins
+    spellings: HashMap<Tokentype, String>,
+}
+
+impl Keywords {
+    // lines are the numbered, comment free rules of the keywords section of a .tokenfile
+    pub fn new(lines: &[(usize, &str)]) -> Result<Self, RloxError> {
+        let map = KEYWORDS
+            .iter()
+            .map(|(word, tokentype)| (word.to_string(), *tokentype))
+            .collect();
+        apply_rules(map, lines)
+    }
+
+    pub fn spelling(&self, tokentype: Tokentype) -> Option<&str> {
+        self.spellings.get(&tokentype).map(String::as_str)
+    }
+}
+
+// a single `native : alias, alias` line of the tokenfile
+struct Rule<'a> {
+    line: usize,
+    native: &'a str,
+    tokentype: Tokentype,
+    aliases: Vec<Vec<&'a str>>,
+}
+
+// rules look like `print : bol_bhai, bol bhai` one per line
+fn apply_rules(
+    mut map: HashMap<String, Tokentype>,
+    lines: &[(usize, &str)],
+) -> Result<Keywords, RloxError> {
+    let mut spellings: HashMap<Tokentype, String> = map
+        .iter()
+        .map(|(word, tokentype)| (*tokentype, word.clone()))
+        .chain(
+            SYMBOLS
+                .iter()
+                .map(|(symbol, tokentype)| (*tokentype, symbol.to_string())),
+        )
+        .collect();
+    let mut rules = Vec::new();
+    for (number, line) in lines {
+        rules.push(parse_rule(&map, *number, line)?);
+    }
+
+    for rule in rules.iter().rev() {
+        spellings.insert(rule.tokentype, rule.aliases[0].join(" "));
+    }
+    // renamed keywords are removed before any alias is added so keywords can swap names
+    for rule in &rules {
+        if map.get(rule.native) == Some(&rule.tokentype) {
+            map.remove(rule.native);
+        }
+    }
+    let mut phrases: HashMap<String, Vec<(Vec<String>, Tokentype)>> = HashMap::new();
+    for rule in &rules {
+        for alias in &rule.aliases {
+            let used_by = match alias[..] {
+                [word] => map.get(word).copied(),
+                _ => phrases.get(alias[0]).and_then(|candidates| {
+                    candidates
+                        .iter()
+                        .find(|(words, _)| words == alias)
+                        .map(|(_, tokentype)| *tokentype)
+                }),
+            };
+            if used_by.is_some_and(|tokentype| tokentype != rule.tokentype) {
+                return Err(config_error(
+                    rule.line,
+                    &format!(
+                        "alias '{}' of '{}' is already used by another token.",
+                        alias.join(" "),
+                        rule.native
+                    ),
+                ));
+            }
+            match alias[..] {
+                [word] => {
+                    map.insert(word.to_string(), rule.tokentype);
+                }
+                _ => {
+                    let words = alias.iter().map(|word| word.to_string()).collect();
+                    phrases
+                        .entry(alias[0].to_string())
+                        .or_default()
+                        .push((words, rule.tokentype));
+                }
+            }
+        }
+    }
+    for candidates in phrases.values_mut() {
+        candidates.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+    }
+    Ok(Keywords {
+        words: map,
+        phrases,
+        spellings,
+    })
+}
+
+fn parse_rule<'a>(
+    map: &HashMap<String, Tokentype>,
+    line: usize,
+    rule: &'a str,
+) -> Result<Rule<'a>, RloxError> {
+    let (native, aliases) = match rule.split_once(':') {
+        Some((native, aliases)) => (native.trim(), aliases),
+        None => {
+            return Err(config_error(
+                line,
+                "expect 'Native_Token_Name : Your_Desired_Token_Name'.",
+            ))
+        }
+    };
+
+    let tokentype = match map.get(native) {
+        Some(tokentype) => *tokentype,
+        None => match SYMBOLS.iter().find(|(symbol, _)| *symbol == native) {
+            Some((_, tokentype)) => *tokentype,
+            None => {
+                return Err(config_error(
+                    line,
+                    &format!("token '{}' specified in .tokenfile not found.", native),
+                ))
+            }
+        },
+    };
+
+    // an alias is made of one or more whitespace separated identifiers
+    let aliases: Vec<Vec<&str>> = aliases
+        .split(',')
+        .map(|alias| alias.split_whitespace().collect())
+        .collect();
+    for alias in &aliases {
+        if alias.is_empty() || !alias.iter().all(|word| is_identifier(word)) {
+            return Err(config_error(
+                line,
+                &format!(
+                    "alias '{}' of '{}' is not a valid identifier.",
+                    alias.join(" "),
+                    native
+                ),
+            ));
+        }
+    }
+
+    Ok(Rule {
+        line,
+        native,
+        tokentype,
+        aliases,
+    })
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut characters = text.chars();
+    match characters.next() {
+        Some(first) => is_alpha(first) && characters.all(is_alpha_numeric),
+        None => false,
+    }
+}
+
+pub(crate) fn config_error(line: usize, message: &str) -> RloxError {
+    RloxError::Config(format!("line {} : {}", line, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(rules: &[&str]) -> Result<Keywords, RloxError> {
+        let lines: Vec<(usize, &str)> = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| (index + 1, *rule))
+            .collect();
+        Keywords::new(&lines)
+    }
+
+    // the message of a rejected tokenfile
+    fn error(rules: &[&str]) -> String {
+        match keywords(rules) {
+            Err(RloxError::Config(message)) => message,
+            Err(_) => unreachable!("rules only fail with config errors"),
+            Ok(_) => panic!("{:?} should be rejected", rules),
+        }
+    }
+
+    #[test]
+    fn renamed_keywords_lose_their_name() {
+        let keywords = keywords(&["print : shout, yell"]).ok().unwrap();
+        assert!(keywords.words.get("shout") == Some(&Tokentype::Print));
+        assert!(keywords.words.get("yell") == Some(&Tokentype::Print));
+        assert!(!keywords.words.contains_key("print"));
+        // the first alias is how the keyword is written
+        assert!(keywords.spelling(Tokentype::Print) == Some("shout"));
+        assert!(keywords.spelling(Tokentype::Var) == Some("var"));
+    }
+
+    #[test]
+    fn keywords_can_swap_names() {
+        let keywords = keywords(&["print : var", "var : print"]).ok().unwrap();
+        assert!(keywords.words.get("var") == Some(&Tokentype::Print));
+        assert!(keywords.words.get("print") == Some(&Tokentype::Var));
+    }
+
+    #[test]
+    fn symbols_and_phrases() {
+        let keywords = keywords(&["{ : hi bhai", "print : bol, bol bhai ji"])
+            .ok()
+            .unwrap();
+        assert!(keywords.spelling(Tokentype::LeftBrace) == Some("hi bhai"));
+        assert!(keywords.words.get("bol") == Some(&Tokentype::Print));
+        let phrases: Vec<&Vec<String>> = keywords.phrases["hi"]
+            .iter()
+            .map(|(words, _)| words)
+            .collect();
+        assert!(phrases == [&vec!["hi".to_string(), "bhai".to_string()]]);
+        // longest phrase first so the scanner matches greedily
+        let lengths: Vec<usize> = keywords.phrases["bol"]
+            .iter()
+            .map(|(words, _)| words.len())
+            .collect();
+        assert!(lengths == [3]);
+    }
+
+    #[test]
+    fn phrases_sharing_a_first_word_are_ordered_longest_first() {
+        let keywords = keywords(&["print : bol bhai", "var : bol bhai ye"])
+            .ok()
+            .unwrap();
+        let tokentypes: Vec<Tokentype> = keywords.phrases["bol"]
+            .iter()
+            .map(|(_, tokentype)| *tokentype)
+            .collect();
+        assert!(tokentypes == [Tokentype::Var, Tokentype::Print]);
+    }
+
+    #[test]
+    fn aliases_collide() {
+        assert!(error(&["print : shout", "var : shout"]).contains("already used"));
+        assert!(error(&["print : and"]).contains("already used"));
+        assert!(error(&["print : bol bhai", "var : bol bhai"]).contains("already used"));
+        // the same alias twice for one token is fine
+        assert!(keywords(&["print : shout, shout"]).is_ok());
+    }
+
+    #[test]
+    fn rules_are_validated() {
+        assert!(error(&["print shout"]).starts_with("line 1 : expect"));
+        assert!(error(&["var : ok", "loop : forever"]).starts_with("line 2 : token 'loop'"));
+        assert!(error(&["print : 1shout"]).contains("not a valid identifier"));
+        assert!(error(&["print : shout,"]).contains("not a valid identifier"));
+        assert!(error(&["print : sh-out"]).contains("not a valid identifier"));
+    }
+}
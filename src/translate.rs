@@ -0,0 +1,86 @@
+use crate::{
+    diagnostic::{Diagnostic, SourceFile},
+    error::RloxError,
+    messages::{Code, Message},
+    scanner::{
+        dialect::Dialect,
+        is_alpha_numeric,
+        token::{Token, Tokentype},
+        Scanner,
+    },
+};
+use std::rc::Rc;
+
+// re-emits source written in the from dialect using the keywords of the to dialect,
+// whitespace, comments and literals between keywords are copied exactly as they are
+pub fn translate(
+    source: Rc<SourceFile>,
+    from: &Rc<Dialect>,
+    to: &Rc<Dialect>,
+) -> Result<String, RloxError> {
+    let tokens = Scanner::new(source.clone(), from.clone())
+        .scan_tokens()?
+        .clone();
+
+    let text = &source.text;
+    let mut output = String::new();
+    let mut last = 0;
+    for token in tokens
+        .iter()
+        .filter(|token| token.tokentype != Tokentype::Eof)
+    {
+        output.push_str(&text[last..token.offset]);
+        last = token.offset + token.lexeme.len();
+        match to.spelling(token.tokentype) {
+            Some(spelling) if spelling.chars().all(|c| is_alpha_numeric(c) || c == ' ') => {
+                if needs_space(&output, spelling) {
+                    output.push(' ');
+                }
+                output.push_str(spelling);
+                if needs_space(spelling, &text[last..]) {
+                    output.push(' ');
+                }
+            }
+            Some(spelling) => output.push_str(spelling),
+            None => output.push_str(&token.lexeme),
+        }
+    }
+    output.push_str(&text[last..]);
+
+    verify(&output, &source.name, to, &tokens)?;
+    Ok(output)
+}
+
+// words must not run into the identifiers, numbers and strings around them
+pub(crate) fn needs_space(left: &str, right: &str) -> bool {
+    match (left.chars().last(), right.chars().next()) {
+        (Some(last), Some(first)) => {
+            (is_alpha_numeric(last) || matches!(last, '"' | ')' | ']')) && is_alpha_numeric(first)
+                || is_alpha_numeric(last) && first == '"'
+        }
+        _ => false,
+    }
+}
+
+// scans the translation back with the target rules, an identifier of the program that is a
+// keyword of the target dialect would silently change what the program does
+fn verify(output: &str, name: &str, to: &Rc<Dialect>, tokens: &[Token]) -> Result<(), RloxError> {
+    let translation = SourceFile::new(name, output.to_string());
+    let mut scanner = Scanner::new(translation, to.clone());
+    let translated = match scanner.scan_tokens() {
+        Ok(translated) => translated,
+        Err(_) => {
+            return Err(RloxError::Translate(Diagnostic::at_token(
+                &tokens[0],
+                &Message::new(Code::Untranslatable, &[]),
+            )))
+        }
+    };
+    for (index, token) in tokens.iter().enumerate() {
+        if translated.get(index).map(|t| t.tokentype) != Some(token.tokentype) {
+            let message = Message::new(Code::DifferentMeaning, &[&token.lexeme]);
+            return Err(RloxError::Translate(Diagnostic::at_token(token, &message)));
+        }
+    }
+    Ok(())
+}
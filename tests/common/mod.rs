@@ -0,0 +1,101 @@
+// runs scripts with their output captured, shared by the integration tests, not every
+// test uses every helper
+#![allow(dead_code)]
+
+use rlox::{
+    diagnostic::SourceFile,
+    error::RloxError,
+    interpreter::{io::Io, limits::Limits, sandbox::Sandbox, Interpreter},
+    parser::{ast::Ast, Parser},
+    presets,
+    scanner::{dialect::Dialect, Scanner},
+};
+use std::{cell::RefCell, io, rc::Rc};
+
+// keeps what scripts print, anything else they try fails
+pub struct Capture(Rc<RefCell<Vec<String>>>);
+
+impl Io for Capture {
+    fn print(&mut self, text: &str) -> io::Result<()> {
+        self.0.borrow_mut().push(text.to_string());
+        Ok(())
+    }
+
+    fn read_line(&mut self, _: &str) -> io::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn read_file(&mut self, _: &str) -> io::Result<String> {
+        Err(io::Error::other("no files in tests"))
+    }
+
+    fn write_file(&mut self, _: &str, _: &str) -> io::Result<()> {
+        Err(io::Error::other("no files in tests"))
+    }
+
+    fn append_file(&mut self, _: &str, _: &str) -> io::Result<()> {
+        Err(io::Error::other("no files in tests"))
+    }
+
+    fn file_exists(&mut self, _: &str) -> io::Result<bool> {
+        Ok(false)
+    }
+
+    fn list_dir(&mut self, _: &str) -> io::Result<Vec<String>> {
+        Err(io::Error::other("no files in tests"))
+    }
+}
+
+// an interpreter along with the lines it prints
+pub fn interpreter() -> (Interpreter, Rc<RefCell<Vec<String>>>) {
+    sandboxed(&Sandbox::default())
+}
+
+// an interpreter whose natives are limited to what sandbox allows
+pub fn sandboxed(sandbox: &Sandbox) -> (Interpreter, Rc<RefCell<Vec<String>>>) {
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let interpreter = Interpreter::sandboxed(Box::new(Capture(output.clone())), sandbox);
+    (interpreter, output)
+}
+
+pub fn parse(source: &str) -> Result<Ast, String> {
+    parse_nested(source, Limits::default().nesting)
+}
+
+// parses source refusing statements and expressions nested deeper than nesting
+pub fn parse_nested(source: &str, nesting: usize) -> Result<Ast, String> {
+    let source = SourceFile::new("test.lox", source.to_string());
+    let tokens = Scanner::new(source, Rc::new(Dialect::default()))
+        .scan_tokens()
+        .map_err(|err| err.to_string())?
+        .to_vec();
+    let mut parser = Parser::new(tokens);
+    parser.set_max_nesting(nesting);
+    parser
+        .parse()
+        .map_err(|errors| RloxError::from(errors).to_string())
+}
+
+// runs source, the error is the diagnostic rlox would report
+pub fn run(interpreter: &mut Interpreter, source: &str) -> Result<(), String> {
+    let ast = parse(source)?;
+    interpreter
+        .interpret(&ast)
+        .map_err(|err| RloxError::from(err).to_string())
+}
+
+// what a script prints in a fresh interpreter, and how it failed if it did
+pub fn output(source: &str) -> (Vec<String>, Result<(), String>) {
+    let (mut interpreter, output) = interpreter();
+    let result = run(&mut interpreter, source);
+    let lines = output.borrow().clone();
+    (lines, result)
+}
+
+// a bundled preset by name, lox is plain lox
+pub fn dialect(name: &str) -> Rc<Dialect> {
+    match presets::find(name) {
+        Some(preset) => Rc::new(Dialect::from_rules(preset.rules).ok().unwrap()),
+        None => Rc::new(Dialect::default()),
+    }
+}
@@ -0,0 +1,77 @@
+use rlox::{diagnostic::SourceFile, format::format, scanner::dialect::Dialect};
+use std::rc::Rc;
+
+fn formatted(source: &str) -> String {
+    let source = SourceFile::new("test.lox", source.to_string());
+    format(source, &Rc::new(Dialect::default()))
+        .map_err(|err| err.to_string())
+        .unwrap()
+}
+
+// comments in the order they are written, as the formatter writes them
+fn comments(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter_map(|line| match (line.find("//"), line.find("/*")) {
+            (Some(start), _) => Some(line[start..].trim_end()),
+            (None, Some(start)) => Some(&line[start..start + line[start..].find("*/")? + 2]),
+            (None, None) => None,
+        })
+        .collect()
+}
+
+const SCRIPTS: [&str; 6] = [
+    "var a=1;var b ;print a+b*2;",
+    "// header\n\n\nvar x = 1; // one\n/* two */ print x;\n",
+    "{ print 1;\n\n  { } // empty\n  { /* only */ }\n}",
+    "if(x){print 1;} // after then\nelse{print 2;}",
+    "if (x)\n  print 1; // one\n// before else\nelse if (y) print 2;\n/* last */ else { print 3; }",
+    "while (i < 3) // loop\n{\ni = i + 1; // step\n}\nprint f(1, \"a\")[0].upper() or !b;",
+];
+
+#[test]
+fn formatting_twice_changes_nothing() {
+    for script in SCRIPTS {
+        let once = formatted(script);
+        assert_eq!(formatted(&once), once, "{}", script);
+    }
+}
+
+#[test]
+fn comments_are_kept_in_order() {
+    for script in SCRIPTS {
+        assert_eq!(comments(&formatted(script)), comments(script), "{}", script);
+    }
+}
+
+#[test]
+fn formatted_source_is_left_alone() {
+    let source = "\
+// adds up
+var total = 0;
+
+while (total < 10) {
+    total = total + 1; // one more
+}
+if (total == 10) {
+    print \"done\";
+} // ten
+else if (total > 10) {
+    print \"over\";
+} else
+    print total;
+";
+    assert_eq!(formatted(source), source);
+}
+
+#[test]
+fn comments_before_else_stay_before_it() {
+    assert_eq!(
+        formatted("if (x) {\n    print 1;\n} // after then\nelse {\n    print 2;\n}\n"),
+        "if (x) {\n    print 1;\n} // after then\nelse {\n    print 2;\n}\n"
+    );
+    assert_eq!(
+        formatted("if (x) print 1;\n// before else\nelse print 2;"),
+        "if (x)\n    print 1;\n// before else\nelse\n    print 2;\n"
+    );
+}
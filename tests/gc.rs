@@ -0,0 +1,80 @@
+mod common;
+
+use common::{interpreter, run};
+use rlox::interpreter::limits::Limits;
+
+#[test]
+fn unreachable_cycles_are_freed() {
+    let (mut interpreter, output) = interpreter();
+    let script = r#"
+        var a = list(1, "two");
+        a.push(a);
+        print a;
+        a = nil;
+        print gc() > 0;
+    "#;
+    run(&mut interpreter, script).unwrap();
+    assert_eq!(*output.borrow(), ["[1, two, [...]]", "True"]);
+    assert_eq!(interpreter.gc_stats().freed_objects, 1);
+    assert_eq!(interpreter.memory_usage(), 0);
+}
+
+#[test]
+fn reachable_cycles_survive() {
+    let (mut interpreter, output) = interpreter();
+    let script = r#"
+        var a = list();
+        a.push(a);
+        {
+            var b = list(a);
+            a.push(b);
+            print gc();
+        }
+        print len(a);
+        print a[1][0] == a;
+    "#;
+    run(&mut interpreter, script).unwrap();
+    assert_eq!(*output.borrow(), ["0", "2", "True"]);
+    assert_eq!(interpreter.gc_stats().freed_objects, 0);
+}
+
+// values an expression is still working with are on the stack of the interpreter
+#[test]
+fn temporaries_survive_collections() {
+    let (mut interpreter, output) = interpreter();
+    interpreter.set_gc_stress(true);
+    let script = r#"
+        print list(split("a b"), gc(), split("c d"));
+        print split("a b")[len(list(gc()))];
+        print split("a b") == list("a", gc());
+    "#;
+    run(&mut interpreter, script).unwrap();
+    assert_eq!(*output.borrow(), ["[[a, b], 0, [c, d]]", "b", "False"]);
+}
+
+#[test]
+fn garbage_cycles_are_collected_before_the_memory_limit_is_hit() {
+    let (mut interpreter, output) = interpreter();
+    interpreter.set_limits(Limits {
+        memory: Some(2000),
+        ..Limits::default()
+    });
+    let script = r#"
+        var i = 0;
+        while (i < 100) {
+            var a = list(repeat("x", 100));
+            a.push(a);
+            i = i + 1;
+        }
+        print "done";
+    "#;
+    run(&mut interpreter, script).unwrap();
+    assert_eq!(*output.borrow(), ["done"]);
+    assert!(interpreter.gc_stats().freed_objects > 0);
+    let error = run(
+        &mut interpreter,
+        r#"var b = list(); b.push(repeat("x", 2000));"#,
+    )
+    .unwrap_err();
+    assert!(error.contains("E0321"), "{}", error);
+}
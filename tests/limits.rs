@@ -0,0 +1,114 @@
+mod common;
+
+use common::{interpreter, parse, parse_nested, run};
+use rlox::interpreter::limits::Limits;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn sleep_stops_at_the_deadline() {
+    let (mut interpreter, _) = interpreter();
+    interpreter.set_limits(Limits {
+        timeout: Some(Duration::from_millis(100)),
+        ..Limits::default()
+    });
+    let start = Instant::now();
+    let error = run(&mut interpreter, "sleep(1e9);").unwrap_err();
+    assert!(error.contains("E0318"), "{}", error);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn sleep_wakes_up_for_an_interrupt() {
+    let (mut interpreter, _) = interpreter();
+    let handle = interpreter.interrupt_handle();
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        handle.interrupt();
+    });
+    let start = Instant::now();
+    let error = run(&mut interpreter, "sleep(5);").unwrap_err();
+    interrupter.join().unwrap();
+    assert!(error.contains("E0320"), "{}", error);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn an_interrupt_before_a_script_stops_it() {
+    let (mut interpreter, output) = interpreter();
+    interpreter.interrupt_handle().interrupt();
+    let error = run(&mut interpreter, "print 1;").unwrap_err();
+    assert!(error.contains("E0320"), "{}", error);
+    assert!(output.borrow().is_empty());
+    // reporting the interrupt clears it
+    run(&mut interpreter, "print 2;").unwrap();
+    assert_eq!(*output.borrow(), ["2"]);
+}
+
+#[test]
+fn short_sleeps_finish() {
+    let (mut interpreter, output) = interpreter();
+    run(&mut interpreter, "sleep(0.01); print 1;").unwrap();
+    assert_eq!(*output.borrow(), ["1"]);
+}
+
+#[test]
+fn shared_strings_count_once() {
+    let (mut interpreter, _) = interpreter();
+    interpreter.set_limits(Limits {
+        memory: Some(100),
+        ..Limits::default()
+    });
+    run(
+        &mut interpreter,
+        r#"var a = repeat("x", 60); { var b = a; }"#,
+    )
+    .unwrap();
+    assert_eq!(interpreter.memory_usage(), 60);
+    run(&mut interpreter, "var b = a; var c = b;").unwrap();
+    assert_eq!(interpreter.memory_usage(), 60);
+    run(&mut interpreter, "a = nil; b = nil;").unwrap();
+    assert_eq!(interpreter.memory_usage(), 60);
+    run(&mut interpreter, "c = nil;").unwrap();
+    assert_eq!(interpreter.memory_usage(), 0);
+    let error = run(
+        &mut interpreter,
+        r#"var a = repeat("x", 60); var b = a + a;"#,
+    )
+    .unwrap_err();
+    assert!(error.contains("E0321"), "{}", error);
+}
+
+#[test]
+fn nesting_is_configurable() {
+    let source = format!("print {}1{};", "(".repeat(10), ")".repeat(10));
+    assert!(parse(&source).is_ok());
+    let error = parse_nested(&source, 5).err().unwrap();
+    assert!(error.contains("Too deeply nested."), "{}", error);
+    assert!(parse_nested("print 1 + 2 + 3;", 5).is_ok());
+}
+
+#[test]
+fn the_deepest_script_the_default_allows_runs() {
+    // the stack a main thread gets, test threads get less
+    let runner = thread::Builder::new().stack_size(8 * 1024 * 1024);
+    let output = runner
+        .spawn(|| {
+            let script =
+                |depth: usize| format!("print {}1{};", "(".repeat(depth), ")".repeat(depth));
+            let deepest = (0..Limits::default().nesting)
+                .rev()
+                .find(|depth| parse(&script(*depth)).is_ok())
+                .unwrap();
+            let (mut interpreter, output) = interpreter();
+            run(&mut interpreter, &script(deepest)).unwrap();
+            let lines = output.borrow().clone();
+            lines
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(output, ["1"]);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::output;
+
+// the code of the error source stops with
+fn error(source: &str) -> String {
+    let (_, result) = output(source);
+    let error = result.unwrap_err();
+    error[error.find('[').unwrap() + 1..error.find(']').unwrap()].to_string()
+}
+
+fn printed(source: &str) -> Vec<String> {
+    let (lines, result) = output(source);
+    assert!(result.is_ok(), "{}", result.unwrap_err());
+    lines
+}
+
+#[test]
+fn int_results_that_overflow_are_errors() {
+    assert_eq!(printed("print pow(2, 62);"), ["4611686018427387904"]);
+    assert_eq!(error("pow(2, 63);"), "E0308");
+    assert_eq!(
+        printed("print abs(-9223372036854775807);"),
+        ["9223372036854775807"]
+    );
+    assert_eq!(error("abs(-9223372036854775807 - 1);"), "E0308");
+}
+
+#[test]
+fn rounding_out_of_the_int_range_is_an_error() {
+    assert_eq!(printed("print floor(-2.5); print round(2.5);"), ["-3", "3"]);
+    assert_eq!(error("floor(1e300);"), "E0308");
+    assert_eq!(error("round(-1e19);"), "E0308");
+    assert_eq!(error("ceil(0 / 0.0);"), "E0308");
+}
+
+#[test]
+fn min_and_max_need_a_value() {
+    assert_eq!(
+        printed("print min(3, 1, 2); print max(list(3, 1, 2));"),
+        ["1", "3"]
+    );
+    assert_eq!(error("min(list());"), "E0309");
+    assert_eq!(error("max(list());"), "E0309");
+}
+
+#[test]
+fn log_needs_a_base_it_can_use() {
+    assert_eq!(printed("print log(8, 2);"), ["3"]);
+    assert_eq!(error("log(0);"), "E0307");
+    assert_eq!(error("log(8, 1);"), "E0307");
+    assert_eq!(error("log(8, 0);"), "E0307");
+    assert_eq!(error("log(8, -2);"), "E0307");
+}
+
+#[test]
+fn seeded_randint_stays_within_its_bounds() {
+    let script = "
+        seed(7);
+        var low = 10;
+        var high = 0;
+        var i = 0;
+        while (i < 500) {
+            var r = randint(1, 3);
+            low = min(low, r);
+            high = max(high, r);
+            i = i + 1;
+        }
+        print low;
+        print high;
+        seed(7);
+        print randint(1, 1000000);
+    ";
+    let lines = printed(script);
+    assert_eq!(lines[..2], ["1", "3"]);
+    assert_eq!(printed(script), lines);
+    assert_eq!(error("randint(3, 1);"), "E0307");
+}
+
+#[test]
+fn strings_count_characters_not_bytes() {
+    let script = r#"
+        var word = "héllo";
+        print len(word);
+        print word.upper();
+        print word[1];
+        print substr(word, 1, 3);
+        print index_of(word, "l");
+        print split("a,é,c", ",");
+        print "ß".upper();
+    "#;
+    assert_eq!(
+        printed(script),
+        ["5", "HÉLLO", "é", "éll", "2", "[a, é, c]", "SS"]
+    );
+    assert_eq!(error(r#"print "héllo"[5];"#), "E0310");
+    assert_eq!(error(r#"print "héllo"[-1];"#), "E0310");
+}
+
+#[test]
+fn int_reads_numbers_strings_and_bools() {
+    let script = r#"print int(-3.7); print int("0x10"); print int(true); print int(" 42 ");"#;
+    assert_eq!(printed(script), ["-3", "16", "1", "42"]);
+    assert_eq!(error(r#"int("x");"#), "E0313");
+    assert_eq!(error("int(nil);"), "E0306");
+    assert_eq!(error("int(1e300);"), "E0308");
+    assert_eq!(error("int(0 / 0.0);"), "E0308");
+}
+
+#[test]
+fn push_adds_to_the_end_of_a_list() {
+    let script = "var a = list(1); print a.push(2); push(a, list()); print a; print len(a);";
+    assert_eq!(printed(script), ["Nil", "[1, 2, []]", "3"]);
+    assert_eq!(error("push(1, 2);"), "E0306");
+}
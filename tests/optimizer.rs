@@ -0,0 +1,76 @@
+mod common;
+
+use common::{interpreter, parse};
+use rlox::{error::RloxError, interpreter::limits::Limits, optimizer};
+
+type Run = (Vec<String>, Result<(), String>);
+
+// what source prints and how it fails, run as it is and after the optimizer went over it
+fn runs(source: &str, memory: Option<usize>) -> (Run, Run) {
+    let run = |optimize: bool| {
+        let (mut interpreter, output) = interpreter();
+        interpreter.set_limits(Limits {
+            memory,
+            ..Limits::default()
+        });
+        let mut ast = parse(source).unwrap();
+        if optimize {
+            optimizer::optimize(&mut ast);
+        }
+        let result = interpreter
+            .interpret(&ast)
+            .map_err(|err| RloxError::from(err).to_string());
+        let lines = output.borrow().clone();
+        (lines, result)
+    };
+    (run(false), run(true))
+}
+
+fn same(source: &str, memory: Option<usize>) -> Run {
+    let (plain, optimized) = runs(source, memory);
+    assert_eq!(plain, optimized, "{}", source);
+    plain
+}
+
+#[test]
+fn folding_prints_the_same() {
+    let scripts = [
+        "print 60 * 60 * 24;",
+        "print -(2.5) + 1 / 4;",
+        r#"print "a" + "b" == "ab";"#,
+        "print 1 < 2 and 3 or 4;",
+        "print 0 or nil;",
+        r#"print !"" + 1;"#,
+        "if (1 < 2) print 1; else print 2;",
+        "if (1) print 1; else print 2;",
+        "while (false) print 1; print 2;",
+        "var x = 2; print x * (3 + 4);",
+    ];
+    for script in scripts {
+        let (lines, result) = same(script, None);
+        assert!(result.is_ok(), "{}", script);
+        assert!(!lines.is_empty(), "{}", script);
+    }
+}
+
+#[test]
+fn errors_are_the_same() {
+    let scripts = [
+        "print 9223372036854775807 + 1;",
+        "print -(-9223372036854775807 - 1);",
+        r#"print "abc"[5];"#,
+        "print 1; print 2 * 4611686018427387904;",
+    ];
+    for script in scripts {
+        let (_, result) = same(script, None);
+        assert!(result.is_err(), "{}", script);
+    }
+}
+
+#[test]
+fn joined_strings_count_against_the_memory_limit() {
+    let source = format!(r#"print "{}" + "{}";"#, "x".repeat(60), "y".repeat(60));
+    let (_, result) = same(&source, Some(100));
+    let error = result.unwrap_err();
+    assert!(error.contains("E0321"), "{}", error);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{run, sandboxed};
+use rlox::interpreter::sandbox::{Capability, Sandbox};
+
+#[test]
+fn a_locked_sandbox_denies_what_reaches_outside() {
+    let (mut interpreter, output) = sandboxed(&Sandbox::locked());
+    for call in [
+        r#"read_file("notes.txt");"#,
+        r#"env("HOME");"#,
+        "clock();",
+        "input();",
+    ] {
+        let error = run(&mut interpreter, call).unwrap_err();
+        assert!(error.contains("E0316"), "{}", error);
+    }
+    run(&mut interpreter, "print abs(-1);").unwrap();
+    assert_eq!(*output.borrow(), ["1"]);
+}
+
+#[test]
+fn allowing_a_group_enables_only_its_natives() {
+    let mut sandbox = Sandbox::locked();
+    sandbox.allow(Capability::Fs);
+    let (mut interpreter, output) = sandboxed(&sandbox);
+    run(&mut interpreter, r#"print file_exists("notes.txt");"#).unwrap();
+    assert_eq!(*output.borrow(), ["False"]);
+    let error = run(&mut interpreter, r#"env("HOME");"#).unwrap_err();
+    assert!(error.contains("E0316"), "{}", error);
+    assert!(error.contains("permission denied: env"), "{}", error);
+}
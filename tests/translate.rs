@@ -0,0 +1,40 @@
+mod common;
+
+use common::dialect;
+use rlox::{diagnostic::SourceFile, translate::translate};
+
+fn translated(source: &str, from: &str, to: &str) -> Result<String, String> {
+    let source = SourceFile::new("test.lox", source.to_string());
+    translate(source, &dialect(from), &dialect(to)).map_err(|err| err.to_string())
+}
+
+#[test]
+fn translating_there_and_back_gives_the_program_back() {
+    let source = "\
+// counts to three
+var i = 0;
+while (i < 3) {
+    if (i == 1 and true) print \"one\"; else print i;
+    i = i + 1;
+}
+";
+    let pirate = translated(source, "lox", "pirate").unwrap();
+    assert!(pirate.contains("booty i = 0;"), "{}", pirate);
+    assert!(
+        pirate.contains("mayhaps (i == 1 aye_and aye)"),
+        "{}",
+        pirate
+    );
+    assert_eq!(translated(&pirate, "pirate", "lox").unwrap(), source);
+}
+
+#[test]
+fn words_that_change_meaning_are_refused() {
+    let error = translated("var booty = 1; print booty;", "lox", "pirate").unwrap_err();
+    assert!(error.contains("E0401"), "{}", error);
+    assert!(
+        error.contains("'booty' has a different meaning"),
+        "{}",
+        error
+    );
+}